@@ -0,0 +1,40 @@
+//! Options of the day binaries and the lib modules they use.
+//!
+//! Nothing owns a day's command line as a whole: each module looks for its own options wherever
+//! they are and leaves the rest alone, so a value is only ever read, never taken out.
+
+use color_eyre::{eyre::bail, Result};
+use std::{env, fmt::Display, str::FromStr, time::Duration};
+
+/// The value after `name` on the command line, if it's there.
+pub fn arg_value<T>(name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(idx) = args.iter().position(|a| a == name) else {
+        return Ok(None);
+    };
+    // another option is no more a value than the end of the line is
+    let value = match args.get(idx + 1) {
+        Some(value) if !value.starts_with("--") => value,
+        _ => bail!("{name} needs a value"),
+    };
+    match value.parse() {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => bail!("bad value {value:?} for {name}: {e}"),
+    }
+}
+
+/// A number of seconds after `name`, which has to make sense as a `Duration`.
+pub fn arg_seconds(name: &str) -> Result<Option<Duration>> {
+    let Some(value) = arg_value::<String>(name)? else {
+        return Ok(None);
+    };
+    // not a number, negative, NaN or too big for a Duration
+    match value.parse().map(Duration::try_from_secs_f64) {
+        Ok(Ok(duration)) => Ok(Some(duration)),
+        _ => bail!("bad value {value:?} for {name}: expected seconds"),
+    }
+}
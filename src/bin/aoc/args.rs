@@ -0,0 +1,88 @@
+//! Just enough argument handling for the `aoc` subcommands.
+
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use std::{str::FromStr, time::Duration};
+
+pub struct Args {
+    args: Vec<String>,
+    passthrough: Vec<String>,
+}
+
+impl Args {
+    pub fn from_env() -> Args {
+        let mut args: Vec<String> = std::env::args().skip(1).collect();
+        // everything after `--` goes to the day binary untouched
+        let passthrough = match args.iter().position(|a| a == "--") {
+            Some(idx) => args.split_off(idx).into_iter().skip(1).collect(),
+            None => vec![],
+        };
+        Args { args, passthrough }
+    }
+
+    pub fn opt(&mut self, name: &str) -> Result<Option<String>> {
+        match self.args.iter().position(|a| a == name) {
+            Some(idx) => {
+                let value = self
+                    .args
+                    .get(idx + 1)
+                    .with_context(|| format!("{name} needs a value"))?
+                    .clone();
+                self.args.drain(idx..=idx + 1);
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.args.iter().position(|a| a == name) {
+            Some(idx) => {
+                self.args.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn parsed_opt<T>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        Ok(self.opt(name)?.map(|v| v.parse()).transpose()?)
+    }
+
+    /// A number of seconds, which has to make sense as a `Duration`.
+    pub fn seconds_opt(&mut self, name: &str) -> Result<Option<Duration>> {
+        let Some(secs) = self.parsed_opt::<f64>(name)? else {
+            return Ok(None);
+        };
+        match Duration::try_from_secs_f64(secs) {
+            Ok(duration) => Ok(Some(duration)),
+            Err(_) => bail!("bad value {secs} for {name}"),
+        }
+    }
+
+    pub fn positional(&mut self, what: &str) -> Result<String> {
+        match self.args.iter().position(|a| !a.starts_with("--")) {
+            Some(idx) => Ok(self.args.remove(idx)),
+            None => bail!("missing {what}"),
+        }
+    }
+
+    pub fn optional_positional(&mut self) -> Option<String> {
+        let idx = self.args.iter().position(|a| !a.starts_with("--"))?;
+        Some(self.args.remove(idx))
+    }
+
+    /// Complain about anything left over, and hand back the arguments meant for the day.
+    pub fn finish(self) -> Result<Vec<String>> {
+        if !self.args.is_empty() {
+            bail!("unexpected arguments: {:?}", self.args);
+        }
+        Ok(self.passthrough)
+    }
+}
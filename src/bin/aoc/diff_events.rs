@@ -0,0 +1,37 @@
+//! `aoc diff-events A B`: compare two `--record` event logs step by step, say from two versions
+//! of a day, and show where they first disagree.
+
+use crate::args::Args;
+use aoc2022::events::{self, Record};
+use color_eyre::{eyre::bail, Result};
+use serde_json::Value;
+use std::path::PathBuf;
+
+fn describe(record: Option<&Record<Value>>) -> String {
+    match record {
+        Some(record) => format!("step {}: {}", record.step, record.event),
+        None => "(log ends)".to_owned(),
+    }
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let a = PathBuf::from(args.positional("first log")?);
+    let b = PathBuf::from(args.positional("second log")?);
+    args.finish()?;
+    let a_events: Vec<Record<Value>> = events::read(&a)?;
+    let b_events: Vec<Record<Value>> = events::read(&b)?;
+    let diverged =
+        (0..a_events.len().max(b_events.len())).find(|&idx| a_events.get(idx) != b_events.get(idx));
+    match diverged {
+        None => {
+            println!("logs agree ({} events)", a_events.len());
+            Ok(())
+        }
+        Some(idx) => {
+            println!("logs agree for {idx} events, then");
+            println!("  {}: {}", a.display(), describe(a_events.get(idx)));
+            println!("  {}: {}", b.display(), describe(b_events.get(idx)));
+            bail!("logs differ")
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! `aoc diff-impls`: run every implementation of the days that have more than one (see
+//! `aoc2022::impls`) on the same inputs and report where they disagree.
+//!
+//! The inputs are each day's files under `--inputs` (skipped with `--no-inputs`) and
+//! `--generated N` random ones from `--seed S`. An input they disagree on is saved so it can be
+//! handed to `aoc minimize` with the `differs` predicate.
+
+use crate::{args::Args, run::runs};
+use aoc2022::{
+    days::{self, Day, DAYS},
+    exec::{self, Outcome},
+    generate::{self, Rng},
+    puzzle::meta_path_for,
+    Puzzle,
+};
+use color_eyre::{eyre::bail, Result};
+use std::{fs, path::PathBuf, time::Duration};
+
+struct Case {
+    name: String,
+    input: String,
+    params: Vec<(String, String)>,
+}
+
+/// What a run came to, in a form two runs can be compared by.
+fn summary(outcome: &Outcome) -> String {
+    if outcome.timed_out() {
+        return format!("timed out after {:.2?}", outcome.elapsed);
+    }
+    let mut parts: Vec<String> = outcome
+        .answers
+        .iter()
+        .map(|(part, answer)| format!("part {part} = {answer}"))
+        .collect();
+    if !outcome.success() {
+        parts.push("failed".to_owned());
+    }
+    if parts.is_empty() {
+        parts.push("no answers".to_owned());
+    }
+    parts.join(", ")
+}
+
+fn run_impl(day: &Day, name: &str, case: &Case, timeout: Duration) -> Result<String> {
+    let mut summaries = vec![];
+    for run_part in runs(day, None) {
+        let mut cmd = day.command(run_part)?;
+        cmd.args(["--impl", name]);
+        for (k, v) in &case.params {
+            cmd.args(["--set".to_owned(), format!("{k}={v}")]);
+        }
+        summaries.push(summary(&exec::run(
+            cmd,
+            case.input.as_bytes(),
+            Some(timeout),
+        )?));
+    }
+    Ok(summaries.join("; "))
+}
+
+fn cases(day: &Day, inputs: Option<&PathBuf>, generated: usize, seed: u64) -> Result<Vec<Case>> {
+    let mut cases = vec![];
+    for path in inputs.map(|dir| day.inputs(dir)).unwrap_or_default() {
+        let params = match meta_path_for(&path) {
+            Some(meta) => Puzzle::from_file(&meta)?
+                .params()
+                .clone()
+                .into_iter()
+                .collect(),
+            None => vec![],
+        };
+        cases.push(Case {
+            name: path.display().to_string(),
+            input: fs::read_to_string(&path)?,
+            params,
+        });
+    }
+    let mut rng = Rng::new(seed);
+    for n in 1..=generated {
+        // small inputs are where the edge cases are, and the easiest to read when they disagree
+        let size = 1 + (n - 1) % 10;
+        let Some(gen) = generate::generate(day.name, size, &mut rng) else {
+            break;
+        };
+        cases.push(Case {
+            name: format!("generated input {n} (seed {seed})"),
+            input: gen.input,
+            params: gen.params,
+        });
+    }
+    Ok(cases)
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let inputs = PathBuf::from(args.opt("--inputs")?.unwrap_or_else(|| "inputs".to_owned()));
+    let no_inputs = args.flag("--no-inputs");
+    let generated = args.parsed_opt("--generated")?.unwrap_or(20);
+    let seed = args.parsed_opt("--seed")?.unwrap_or(2022);
+    let timeout = Duration::from_secs_f64(args.parsed_opt("--timeout")?.unwrap_or(60.0));
+    // after the options, so their values aren't taken for the day
+    let day = args.optional_positional();
+    args.finish()?;
+    let days: Vec<&Day> = match day {
+        Some(name) => {
+            let day = days::find(&name)?;
+            if !day.has_impls {
+                bail!("{} only has the one implementation", day.name);
+            }
+            vec![day]
+        }
+        None => DAYS.iter().filter(|d| d.has_impls).collect(),
+    };
+
+    let mut disagreements = 0;
+    for day in days {
+        let impls = day.impls()?;
+        let cases = cases(day, (!no_inputs).then_some(&inputs), generated, seed)?;
+        let mut agreed = 0;
+        for case in &cases {
+            let results = impls
+                .iter()
+                .map(|name| Ok((name, run_impl(day, name, case, timeout)?)))
+                .collect::<Result<Vec<_>>>()?;
+            if results.iter().all(|(_, r)| *r == results[0].1) {
+                agreed += 1;
+                continue;
+            }
+            disagreements += 1;
+            let saved = exec::scratch_path(&format!("{}-disagreement", day.name));
+            fs::write(&saved, &case.input)?;
+            println!("{} {}: implementations disagree", day.name, case.name);
+            for (name, result) in &results {
+                println!("  {name}: {result}");
+            }
+            println!("  input saved to {}", saved.display());
+        }
+        println!(
+            "{}: {} agree on {agreed} of {} inputs",
+            day.name,
+            impls.join(", "),
+            cases.len()
+        );
+    }
+    if disagreements > 0 {
+        bail!("implementations disagree on {disagreements} inputs");
+    }
+    Ok(())
+}
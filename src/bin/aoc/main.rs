@@ -0,0 +1,43 @@
+//! Tools that work on the day binaries rather than being one.
+//!
+//! ```text
+//! aoc run DAY INPUT [--part N] [--no-cache] [--timeout SECS] [-- DAY ARGS]
+//! aoc cache-clear [DAY]
+//! aoc serve [--port PORT] [--time-limit SECS] [--read-timeout SECS] [--max-connections N]
+//!           [--no-cache]
+//! aoc tui [--inputs DIR] [--time-limit SECS]
+//! aoc diff-events LOG LOG
+//! aoc diff-impls [DAY] [--generated N] [--seed S] [--inputs DIR] [--no-inputs] [--timeout SECS]
+//! aoc profile DAY [--sizes N,N,..] [--truncate] [--input PATH] [--seed S] [--repeat N] [--timeout SECS] [--max-exponent E] [--plot PATH] [-- DAY ARGS]
+//! aoc minimize DAY INPUT [--predicate P] [--reference CMD] [--unit U] [--timeout SECS] [--out PATH] [-- DAY ARGS]
+//! ```
+
+mod args;
+mod diff_events;
+mod diff_impls;
+mod minimize;
+mod profile;
+mod run;
+mod serve;
+mod tui;
+
+use args::Args;
+use color_eyre::{eyre::bail, Result};
+
+const USAGE: &str = "usage: aoc run|cache-clear|serve|tui|diff-events|diff-impls|profile|minimize ...";
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let mut args = Args::from_env();
+    match args.optional_positional().as_deref() {
+        Some("run") => run::main(args),
+        Some("cache-clear") => run::clear(args),
+        Some("serve") => serve::main(args),
+        Some("tui") => tui::main(args),
+        Some("diff-events") => diff_events::main(args),
+        Some("diff-impls") => diff_impls::main(args),
+        Some("profile") => profile::main(args),
+        Some("minimize") => minimize::main(args),
+        _ => bail!(USAGE),
+    }
+}
@@ -0,0 +1,149 @@
+//! `aoc minimize`: shrink an input that makes a day misbehave.
+//!
+//! Predicates, each of which the shrunk input must still satisfy:
+//! - `panics`, or `panics:TEXT` to also require TEXT in the panic output
+//! - `nonzero`: the day exits with an error
+//! - `differs`: the day's answers differ from those of `--reference CMD`, a shell command given
+//!   the same input on stdin. The reference's answers are whatever it reports through
+//!   `AOC_ANSWERS` if it's one of ours, and its stdout lines (part 1, part 2) otherwise.
+//! - `cmd:SCRIPT`: the shell script exits 0 when given the input on stdin, with `AOC_DAY_EXE`
+//!   set to the day binary
+
+use crate::args::Args;
+use aoc2022::{
+    days::{self, Day, InputShape},
+    exec::{self, Outcome},
+    minimize::minimize,
+    puzzle::meta_path_for,
+    Puzzle,
+};
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use std::{collections::BTreeMap, fs, path::PathBuf, time::Duration};
+
+enum Predicate {
+    Panics(Option<String>),
+    Nonzero,
+    Differs(String),
+    Command(String),
+}
+
+impl Predicate {
+    fn parse(spec: &str, reference: Option<String>) -> Result<Predicate> {
+        Ok(match spec.split_once(':') {
+            None if spec == "panics" => Predicate::Panics(None),
+            None if spec == "nonzero" => Predicate::Nonzero,
+            None if spec == "differs" => {
+                Predicate::Differs(reference.context("differs needs --reference CMD")?)
+            }
+            Some(("panics", text)) => Predicate::Panics(Some(text.to_owned())),
+            Some(("cmd", script)) => Predicate::Command(script.to_owned()),
+            _ => bail!("unknown predicate {spec:?}"),
+        })
+    }
+}
+
+struct Tester<'a> {
+    day: &'a Day,
+    day_args: Vec<String>,
+    predicate: Predicate,
+    timeout: Duration,
+    tests: usize,
+}
+
+impl Tester<'_> {
+    fn run_day(&self, input: &str) -> Result<Outcome> {
+        let mut cmd = exec::day_command(self.day.name)?;
+        cmd.args(&self.day_args);
+        exec::run(cmd, input.as_bytes(), Some(self.timeout))
+    }
+
+    fn interesting(&mut self, input: &str) -> Result<bool> {
+        self.tests += 1;
+        Ok(match &self.predicate {
+            Predicate::Panics(text) => {
+                let outcome = self.run_day(input)?;
+                outcome.panicked()
+                    && text
+                        .as_ref()
+                        .map(|t| outcome.stderr.contains(t.as_str()))
+                        .unwrap_or(true)
+            }
+            Predicate::Nonzero => {
+                let outcome = self.run_day(input)?;
+                !outcome.timed_out() && !outcome.success()
+            }
+            Predicate::Differs(reference) => {
+                let outcome = self.run_day(input)?;
+                let refout =
+                    exec::run(exec::shell_command(reference), input.as_bytes(), Some(self.timeout))?;
+                if !outcome.success() || !refout.success() {
+                    return Ok(false);
+                }
+                let ref_answers = if refout.answers.is_empty() {
+                    (1..)
+                        .zip(refout.stdout.lines().map(str::trim).filter(|l| !l.is_empty()))
+                        .map(|(part, answer)| (part, answer.to_owned()))
+                        .collect::<BTreeMap<u8, String>>()
+                } else {
+                    refout.answers
+                };
+                outcome.answers != ref_answers
+            }
+            Predicate::Command(script) => {
+                let mut cmd = exec::shell_command(script);
+                cmd.env("AOC_DAY_EXE", exec::day_exe(self.day.name)?);
+                exec::run(cmd, input.as_bytes(), Some(self.timeout))?.success()
+            }
+        })
+    }
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let day = days::find(&args.positional("day")?)?;
+    let input_path = PathBuf::from(args.positional("input file")?);
+    let predicate = args.opt("--predicate")?.unwrap_or_else(|| "panics".to_owned());
+    let reference = args.opt("--reference")?;
+    let shape = match args.opt("--unit")?.as_deref() {
+        None => day.shape,
+        Some("lines") => InputShape::Lines,
+        Some("blocks") => InputShape::Blocks,
+        Some("grid") => InputShape::Grid,
+        Some(other) => bail!("unknown unit {other:?}, expected lines, blocks or grid"),
+    };
+    let timeout = Duration::from_secs_f64(args.parsed_opt("--timeout")?.unwrap_or(10.0));
+    let out = args.opt("--out")?;
+    let mut day_args = args.finish()?;
+
+    // keep the input's parameters, but not its expected answers, they won't hold for a fragment
+    if let Some(meta) = meta_path_for(&input_path) {
+        for (k, v) in Puzzle::from_file(&meta)?.params() {
+            day_args.extend(["--set".to_owned(), format!("{k}={v}")]);
+        }
+    }
+    let mut tester = Tester {
+        day,
+        day_args,
+        predicate: Predicate::parse(&predicate, reference)?,
+        timeout,
+        tests: 0,
+    };
+    let input = fs::read_to_string(&input_path)?;
+    if !tester.interesting(&input)? {
+        bail!("{} doesn't satisfy {predicate}", input_path.display());
+    }
+    let minimized = minimize(&input, shape, |candidate| tester.interesting(candidate))?;
+    eprintln!(
+        "{} tests, {} -> {} bytes",
+        tester.tests,
+        input.len(),
+        minimized.len()
+    );
+    match out {
+        Some(path) => fs::write(path, minimized)?,
+        None => print!("{minimized}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,377 @@
+//! `aoc profile`: how a day's running time grows with the size of its input.
+//!
+//! Each size gets a generated input (see `aoc2022::generate`) if the day has a generator, or else
+//! the first that many lines, blocks or grid rows of the day's biggest input (`--truncate` to
+//! insist on that, `--input PATH` for another input). The parts are timed from the day loading
+//! its puzzle to each answer, the fastest of `--repeat` runs, and a least-squares line through
+//! log(time) against log(size) gives the growth exponent: about 1 for linear, 2 for quadratic.
+//! Fixed costs flatten the smallest sizes, so the exponent is only as good as the sizes are big.
+//!
+//! `--max-exponent E` fails if any part grows faster than that, and `--plot PATH` draws the
+//! timings on log-log axes as an SVG.
+
+use crate::{args::Args, run::runs};
+use aoc2022::{
+    days::{self, Day, InputShape},
+    exec,
+    generate::{self, Rng},
+    puzzle::meta_path_for,
+    Puzzle,
+};
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// An input of a known size.
+struct Case {
+    size: usize,
+    input: String,
+    params: Vec<(String, String)>,
+}
+
+/// How long a part took, or why there's no telling.
+type Timing = Result<Duration, &'static str>;
+
+fn records(input: &str, shape: InputShape) -> Vec<&str> {
+    match shape {
+        InputShape::Lines | InputShape::Grid => input.lines().collect(),
+        InputShape::Blocks => input.trim_end().split("\n\n").collect(),
+    }
+}
+
+fn truncate(input: &str, shape: InputShape, size: usize) -> String {
+    let records = records(input, shape);
+    let kept = &records[..size.min(records.len())];
+    match shape {
+        InputShape::Lines | InputShape::Grid => kept.join("\n") + "\n",
+        InputShape::Blocks => kept.join("\n\n") + "\n",
+    }
+}
+
+/// The day's input with the most in it.
+fn biggest_input(day: &Day) -> Result<PathBuf> {
+    day.inputs(Path::new("inputs"))
+        .into_iter()
+        .max_by_key(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+        .with_context(|| format!("{} has no generator and no inputs to truncate", day.name))
+}
+
+/// Doublings up to `max`, eight of them.
+fn default_sizes(max: usize) -> Vec<usize> {
+    let mut sizes: Vec<usize> = (0..8).rev().map(|k| max >> k).filter(|&s| s > 0).collect();
+    sizes.dedup();
+    sizes
+}
+
+fn cases(day: &Day, args: &ProfileArgs) -> Result<(String, Vec<Case>)> {
+    let mut rng = Rng::new(args.seed);
+    let generated = generate::generate(day.name, 1, &mut rng).is_some();
+    if generated && !args.truncate && args.input.is_none() {
+        let sizes = args.sizes.clone().unwrap_or_else(|| default_sizes(2048));
+        let cases = sizes
+            .into_iter()
+            .map(|size| {
+                let gen = generate::generate(day.name, size, &mut rng).unwrap();
+                Case {
+                    size,
+                    input: gen.input,
+                    params: gen.params,
+                }
+            })
+            .collect();
+        return Ok((format!("generated inputs (seed {})", args.seed), cases));
+    }
+
+    let path = match &args.input {
+        Some(path) => path.clone(),
+        None => biggest_input(day)?,
+    };
+    let input = fs::read_to_string(&path)?;
+    let params: Vec<(String, String)> = match meta_path_for(&path) {
+        Some(meta) => Puzzle::from_file(&meta)?
+            .params()
+            .clone()
+            .into_iter()
+            .collect(),
+        None => vec![],
+    };
+    let total = records(&input, day.shape).len();
+    let sizes = args.sizes.clone().unwrap_or_else(|| default_sizes(total));
+    let mut cases: Vec<Case> = sizes
+        .into_iter()
+        .map(|size| Case {
+            size: size.min(total),
+            input: truncate(&input, day.shape, size),
+            params: params.clone(),
+        })
+        .collect();
+    cases.dedup_by_key(|case| case.size);
+    Ok((format!("{} truncated", path.display()), cases))
+}
+
+/// One run of every part, each timed from the answer before it (or the start).
+fn time_parts(day: &Day, case: &Case, args: &ProfileArgs) -> Result<BTreeMap<u8, Timing>> {
+    let mut timings = BTreeMap::new();
+    for run_part in runs(day, None) {
+        let mut cmd = day.command(run_part)?;
+        cmd.args(&args.day_args);
+        for (k, v) in &case.params {
+            cmd.args(["--set".to_owned(), format!("{k}={v}")]);
+        }
+        let outcome = exec::run(cmd, case.input.as_bytes(), Some(args.timeout))?;
+        let parts = match run_part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        };
+        for part in parts {
+            let timing = match outcome.answered_at.get(&part) {
+                _ if outcome.timed_out() => Err("timed out"),
+                Some(&at) => {
+                    let before = outcome.answered_at.values().filter(|&&t| t < at).max();
+                    Ok(at - before.copied().unwrap_or_default())
+                }
+                None if !outcome.success() => Err("failed"),
+                None => Err("no answer"),
+            };
+            timings.insert(part, timing);
+        }
+    }
+    Ok(timings)
+}
+
+/// The slope of the least-squares line through (ln size, ln time).
+fn exponent(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .map(|&(size, time)| ((size as f64).ln(), time.as_secs_f64().max(1e-9).ln()))
+        .collect();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = logs.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = logs.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    (logs.len() >= 2 && sxx > 0.0).then(|| sxy / sxx)
+}
+
+/// The timings on log-log axes, a line per part.
+fn plot(title: &str, series: &BTreeMap<u8, Vec<(usize, Duration)>>) -> String {
+    const WIDTH: f64 = 640.0;
+    const HEIGHT: f64 = 400.0;
+    const MARGIN: f64 = 60.0;
+    const COLOURS: [&str; 2] = ["#1f77b4", "#d62728"];
+    let points = series.values().flatten();
+    let xs: Vec<f64> = points.clone().map(|p| (p.0 as f64).ln()).collect();
+    let ys: Vec<f64> = points.map(|p| p.1.as_secs_f64().max(1e-9).ln()).collect();
+    let range = |vs: &[f64]| {
+        let lo = vs.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = vs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if hi - lo < 1e-9 {
+            (lo - 0.5, hi + 0.5)
+        } else {
+            (lo, hi)
+        }
+    };
+    let ((x_lo, x_hi), (y_lo, y_hi)) = (range(&xs), range(&ys));
+    let at = |size: usize, time: Duration| {
+        let x = ((size as f64).ln() - x_lo) / (x_hi - x_lo);
+        let y = (time.as_secs_f64().max(1e-9).ln() - y_lo) / (y_hi - y_lo);
+        (
+            MARGIN + x * (WIDTH - 2.0 * MARGIN),
+            HEIGHT - MARGIN - y * (HEIGHT - 2.0 * MARGIN),
+        )
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n"
+    );
+    let (left, right, top, bottom) = (MARGIN, WIDTH - MARGIN, MARGIN, HEIGHT - MARGIN);
+    writeln!(svg, "<text x=\"{left}\" y=\"{}\">{title}</text>", top / 2.0).unwrap();
+    writeln!(
+        svg,
+        "<path d=\"M{left},{top} V{bottom} H{right}\" fill=\"none\" stroke=\"black\"/>"
+    )
+    .unwrap();
+    let time = |ln: f64| format!("{:.2?}", Duration::from_secs_f64(ln.exp()));
+    let labels = [
+        (left, bottom + 20.0, "middle", format!("{:.0}", x_lo.exp())),
+        (right, bottom + 20.0, "middle", format!("{:.0}", x_hi.exp())),
+        (WIDTH / 2.0, bottom + 40.0, "middle", "size".to_owned()),
+        (left - 5.0, bottom, "end", time(y_lo)),
+        (left - 5.0, top + 5.0, "end", time(y_hi)),
+    ];
+    for (x, y, anchor, text) in labels {
+        writeln!(
+            svg,
+            "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"{anchor}\">{text}</text>"
+        )
+        .unwrap();
+    }
+    for (n, (part, points)) in series.iter().enumerate() {
+        let colour = COLOURS[n % COLOURS.len()];
+        let coords: Vec<String> = points
+            .iter()
+            .map(|&(size, time)| {
+                let (x, y) = at(size, time);
+                format!("{x:.1},{y:.1}")
+            })
+            .collect();
+        writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{colour}\"/>",
+            coords.join(" ")
+        )
+        .unwrap();
+        for coord in &coords {
+            let (x, y) = coord.split_once(',').unwrap();
+            writeln!(
+                svg,
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"3\" fill=\"{colour}\"/>"
+            )
+            .unwrap();
+        }
+        let legend = match exponent(points) {
+            Some(e) => format!("part {part}, exponent {e:.2}"),
+            None => format!("part {part}"),
+        };
+        writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" fill=\"{colour}\">{legend}</text>",
+            left + 10.0,
+            top + 15.0 * (n as f64 + 1.0)
+        )
+        .unwrap();
+    }
+    svg + "</svg>\n"
+}
+
+struct ProfileArgs {
+    sizes: Option<Vec<usize>>,
+    input: Option<PathBuf>,
+    truncate: bool,
+    seed: u64,
+    repeat: usize,
+    timeout: Duration,
+    day_args: Vec<String>,
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let sizes = args
+        .opt("--sizes")?
+        .map(|s| {
+            s.split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<usize>, _>>()
+        })
+        .transpose()?;
+    let input = args.opt("--input")?.map(PathBuf::from);
+    let truncate = args.flag("--truncate");
+    let seed = args.parsed_opt("--seed")?.unwrap_or(2022);
+    let repeat = args.parsed_opt("--repeat")?.unwrap_or(3).max(1);
+    let timeout = Duration::from_secs_f64(args.parsed_opt("--timeout")?.unwrap_or(60.0));
+    let max_exponent: Option<f64> = args.parsed_opt("--max-exponent")?;
+    let plot_path = args.opt("--plot")?;
+    let day = days::find(&args.positional("day")?)?;
+    let day_args = args.finish()?;
+    let args = ProfileArgs {
+        sizes,
+        input,
+        truncate,
+        seed,
+        repeat,
+        timeout,
+        day_args,
+    };
+
+    let (source, cases) = cases(day, &args)?;
+    println!("{}: {source}, fastest of {} runs", day.name, args.repeat);
+    let mut table: Vec<(usize, BTreeMap<u8, Timing>)> = vec![];
+    for case in &cases {
+        let mut best: BTreeMap<u8, Timing> = BTreeMap::new();
+        for _ in 0..args.repeat {
+            for (part, timing) in time_parts(day, case, &args)? {
+                let slot = best.entry(part).or_insert(timing);
+                if let (Ok(old), Ok(new)) = (*slot, timing) {
+                    *slot = Ok(old.min(new));
+                } else if timing.is_err() {
+                    *slot = timing;
+                }
+            }
+        }
+        table.push((case.size, best));
+    }
+    // parts the day never answers (day25's second) aren't worth a column
+    let parts: Vec<u8> = [1, 2]
+        .into_iter()
+        .filter(|part| {
+            table
+                .iter()
+                .any(|(_, timings)| timings.get(part) != Some(&Err("no answer")))
+        })
+        .collect();
+
+    let mut header = format!("{:>8}", "size");
+    for part in &parts {
+        write!(header, "{:>14}", format!("part {part}")).unwrap();
+    }
+    println!("{header}");
+    for (size, timings) in &table {
+        let mut row = format!("{size:>8}");
+        for part in &parts {
+            let cell = match timings.get(part) {
+                Some(Ok(time)) => format!("{time:.2?}"),
+                Some(Err(why)) => why.to_string(),
+                None => "-".to_owned(),
+            };
+            write!(row, "{cell:>14}").unwrap();
+        }
+        println!("{row}");
+    }
+
+    let series: BTreeMap<u8, Vec<(usize, Duration)>> = parts
+        .iter()
+        .map(|&part| {
+            let points = table
+                .iter()
+                .filter_map(|(size, timings)| Some((*size, *timings.get(&part)?.as_ref().ok()?)))
+                .collect();
+            (part, points)
+        })
+        .collect();
+    let mut row = format!("{:>8}", "exponent");
+    let mut too_steep = vec![];
+    for (part, points) in &series {
+        let fitted = exponent(points);
+        if let (Some(e), Some(max)) = (fitted, max_exponent) {
+            if e > max {
+                too_steep.push(format!("part {part} grows as size^{e:.2}"));
+            }
+        }
+        let cell = fitted
+            .map(|e| format!("{e:.2}"))
+            .unwrap_or_else(|| "-".to_owned());
+        write!(row, "{cell:>14}").unwrap();
+    }
+    println!("{row}");
+
+    if let Some(path) = plot_path {
+        fs::write(&path, plot(&format!("{}: {source}", day.name), &series))?;
+        println!("plot written to {path}");
+    }
+    if !too_steep.is_empty() {
+        bail!(
+            "{}, more than --max-exponent {}",
+            too_steep.join(" and "),
+            max_exponent.unwrap()
+        );
+    }
+    Ok(())
+}
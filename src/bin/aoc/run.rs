@@ -0,0 +1,173 @@
+//! `aoc run`: solve an input, reusing the cached answers when neither it nor the day changed.
+//!
+//! Days that solve part 2 on request (`--part2` or `PART2`) get one run per part; the rest answer
+//! everything in one run. `--no-cache` always reruns (and refreshes the cache); `aoc cache-clear`
+//! throws the cache away.
+
+use crate::args::Args;
+use aoc2022::{
+    cache::{self, Cache, Entry, Key},
+    days::{self, Day, Parts},
+    exec,
+    puzzle::{meta_path_for, INPUT_VAR},
+};
+use color_eyre::{
+    eyre::{bail, Context},
+    Result,
+};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Why a day didn't produce answers, for callers that care (the server picks a status from it).
+#[derive(Debug)]
+pub enum DayFailed {
+    TimedOut(Duration),
+    Failed(String),
+}
+
+impl fmt::Display for DayFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayFailed::TimedOut(after) => write!(f, "timed out after {after:.2?}"),
+            DayFailed::Failed(stderr) => write!(f, "failed:\n{stderr}"),
+        }
+    }
+}
+
+impl std::error::Error for DayFailed {}
+
+/// The runs it takes to answer `part` (or both parts), as passed to [`Day::command`].
+pub fn runs(day: &Day, part: Option<u8>) -> Vec<Option<u8>> {
+    match (day.parts, part) {
+        (Parts::Together, _) => vec![None],
+        (_, Some(part)) => vec![Some(part)],
+        (_, None) => vec![Some(1), Some(2)],
+    }
+}
+
+/// An input for a day, and how to run it.
+pub struct Job<'a> {
+    pub day: &'a Day,
+    pub input: &'a [u8],
+    /// where the input came from, so the day can find its metadata
+    pub input_path: Option<&'a Path>,
+    pub day_args: &'a [String],
+    pub timeout: Option<Duration>,
+}
+
+impl Job<'_> {
+    /// One run, answered from `cache` if it has it and `reuse` is set. The fresh answers of a
+    /// successful run go into the cache, unless a search was cut short for one of them. Also
+    /// returns whether they came from the cache.
+    pub fn solve(&self, run_part: Option<u8>, cache: &Cache, reuse: bool) -> Result<(Entry, bool)> {
+        let day = self.day;
+        let meta = match self.input_path.and_then(meta_path_for) {
+            Some(path) => fs::read(path)?,
+            None => vec![],
+        };
+        let version = cache::solver_version(&exec::day_exe(day.name)?)?;
+        let key = Key::default()
+            .with(day.name)
+            .with(run_part.map(|p| p.to_string()).unwrap_or_default())
+            .with(self.day_args.join("\0"))
+            .with(self.input)
+            .with(&meta)
+            .with(&version)
+            .finish();
+        if reuse {
+            if let Some(entry) = cache.get(day.name, &key)? {
+                return Ok((entry, true));
+            }
+        }
+        let mut cmd = day.command(run_part)?;
+        cmd.args(self.day_args);
+        if let Some(path) = self.input_path {
+            cmd.env(INPUT_VAR, path);
+        }
+        let outcome = exec::run(cmd, self.input, self.timeout)?;
+        if outcome.timed_out() {
+            return Err(DayFailed::TimedOut(outcome.elapsed).into());
+        }
+        if !outcome.success() {
+            return Err(DayFailed::Failed(outcome.stderr).into());
+        }
+        let entry = Entry {
+            answers: outcome.answers,
+            unproven: outcome.unproven,
+            elapsed: outcome.elapsed,
+        };
+        if entry.unproven.is_empty() {
+            cache.put(day.name, &key, &entry)?;
+        }
+        Ok((entry, false))
+    }
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let day = days::find(&args.positional("day")?)?;
+    let input_path = PathBuf::from(args.positional("input file")?);
+    let part: Option<u8> = args.parsed_opt("--part")?;
+    let no_cache = args.flag("--no-cache");
+    let timeout = args.parsed_opt("--timeout")?.map(Duration::from_secs_f64);
+    let day_args = args.finish()?;
+    if let Some(part) = part {
+        if part != 1 && part != 2 {
+            bail!("there's no part {part}");
+        }
+    }
+
+    let input = fs::read(&input_path)?;
+    let job = Job {
+        day,
+        input: &input,
+        input_path: Some(&input_path),
+        day_args: &day_args,
+        timeout,
+    };
+    let cache = Cache::open()?;
+    for run_part in runs(day, part) {
+        let (entry, was_cached) = job
+            .solve(run_part, &cache, !no_cache)
+            .wrap_err_with(|| format!("running {}", day.name))?;
+        let mut reported = false;
+        for (answer_part, answer) in &entry.answers {
+            if part.map(|p| p == *answer_part).unwrap_or(true) {
+                reported = true;
+                let answer = if entry.unproven.contains(answer_part) {
+                    format!("{answer} NOT proven optimal")
+                } else {
+                    answer.clone()
+                };
+                if was_cached {
+                    println!(
+                        "{} part {answer_part}: {answer} (cached, took {:.2?})",
+                        day.name, entry.elapsed
+                    );
+                } else {
+                    println!(
+                        "{} part {answer_part}: {answer} ({:.2?})",
+                        day.name, entry.elapsed
+                    );
+                }
+            }
+        }
+        if !reported {
+            eprintln!("{} didn't report an answer", day.name);
+        }
+    }
+    Ok(())
+}
+
+/// `aoc cache-clear [DAY]`
+pub fn clear(mut args: Args) -> Result<()> {
+    let day = args.optional_positional();
+    args.finish()?;
+    let day = day.map(|d| days::find(&d)).transpose()?;
+    let cache = Cache::open()?;
+    let removed = cache.clear(day.map(|d| d.name))?;
+    println!("removed {removed} entries from {}", cache.dir().display());
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! `aoc serve`: the solvers over HTTP on localhost, for tools that would rather not shell out.
+//!
+//! `POST /2022/day/{n}` with the input as the request body answers with both parts:
+//!
+//! ```json
+//! {"day":1,"answers":{"1":"24000","2":"45000"},"elapsed_ms":3.2,"cached":false}
+//! ```
+//!
+//! Parts answered by a search cut short by `--time-budget` are listed in `"unproven"`.
+//!
+//! Query parameters go to the day as `--set key=value` (no percent-decoding), as long as they're
+//! among the day's [params](aoc2022::days::Day::params), except `timeout`, which lowers the
+//! server's `--time-limit` (in seconds) for that request. The limit covers the whole request, even
+//! for the days that take a run per part. Answers go through the same cache as `aoc run` unless
+//! the server was started with `--no-cache`.
+//!
+//! At most `--max-connections` requests (8 by default) are handled at once, the rest wait their
+//! turn, and a client has `--read-timeout` seconds (10 by default) for each read of its request.
+//!
+//! Failures come back as `{"error": "..."}`: 400 for a malformed request or a param the day
+//! doesn't take, 404 for an unknown path or day, 405 for anything but POST, 408 for a request that
+//! didn't arrive in time, 422 when the day fails on the input and 504 when the day runs out of
+//! time.
+
+use crate::{
+    args::Args,
+    run::{runs, DayFailed, Job},
+};
+use aoc2022::{cache::Cache, days, exec};
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Bigger than any puzzle input by a long way.
+const MAX_BODY: usize = 16 << 20;
+
+/// How the server treats each connection.
+#[derive(Clone, Copy)]
+struct Limits {
+    time_limit: Duration,
+    read_timeout: Duration,
+}
+
+/// How many connections are being handled, and a way to wait for one to finish.
+#[derive(Default)]
+struct Active {
+    count: Mutex<usize>,
+    finished: Condvar,
+}
+
+/// A connection being handled, counted in [`Active`] until it's done.
+struct Slot(Arc<Active>);
+
+impl Slot {
+    /// Wait until fewer than `max` connections are being handled, then take a place among them.
+    fn take(active: &Arc<Active>, max: usize) -> Slot {
+        let mut count = active.count.lock().unwrap();
+        while *count >= max {
+            count = active.finished.wait(count).unwrap();
+        }
+        *count += 1;
+        Slot(active.clone())
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        *self.0.count.lock().unwrap() -= 1;
+        self.0.finished.notify_one();
+    }
+}
+
+#[derive(Serialize)]
+struct Solution {
+    day: u8,
+    answers: BTreeMap<u8, String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    unproven: BTreeSet<u8>,
+    elapsed_ms: f64,
+    cached: bool,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn error(status: u16, msg: impl std::fmt::Display) -> Response {
+        Response {
+            status,
+            body: serde_json::json!({ "error": msg.to_string() }).to_string(),
+        }
+    }
+}
+
+/// The gist of a day's error report: the messages without colours, location or backtrace.
+fn summarize(stderr: &str) -> String {
+    exec::strip_ansi(stderr)
+        .lines()
+        .skip_while(|l| !l.starts_with("Error:") && !l.contains("panicked"))
+        .take_while(|l| !l.starts_with("Location:"))
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn read_request(stream: &mut BufReader<TcpStream>) -> Result<Request> {
+    let mut line = String::new();
+    stream.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().context("empty request")?.to_owned();
+    let target = parts.next().context("no request target")?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.to_owned();
+    let query = query
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            (k.to_owned(), v.to_owned())
+        })
+        .collect();
+    let mut header_line = String::new();
+    let mut content_length = 0;
+    loop {
+        header_line.clear();
+        if stream.read_line(&mut header_line)? == 0 {
+            break;
+        }
+        let header = header_line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        bail!("request body of {content_length} bytes is too big");
+    }
+    let mut body = vec![0; content_length];
+    stream.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        path,
+        query,
+        body,
+    })
+}
+
+fn respond(req: Request, time_limit: Duration, cache: &Cache, reuse: bool) -> Response {
+    let Some(num) = req.path.strip_prefix("/2022/day/") else {
+        return Response::error(404, format!("no such endpoint {}", req.path));
+    };
+    let day = match days::find(num) {
+        Ok(day) => day,
+        Err(e) => return Response::error(404, e),
+    };
+    if req.method != "POST" {
+        return Response::error(405, "POST the input to this endpoint");
+    }
+    let started = Instant::now();
+    let mut timeout = time_limit;
+    let mut day_args = vec![];
+    for (k, v) in &req.query {
+        if k == "timeout" {
+            match v.parse().map(Duration::try_from_secs_f64) {
+                Ok(Ok(secs)) if !secs.is_zero() => timeout = timeout.min(secs),
+                _ => return Response::error(400, format!("bad timeout {v:?}")),
+            }
+        } else if day.params.contains(&k.as_str()) {
+            day_args.extend(["--set".to_owned(), format!("{k}={v}")]);
+        } else {
+            return Response::error(400, format!("{} doesn't take a param {k:?}", day.name));
+        }
+    }
+    let deadline = started + timeout;
+    let mut job = Job {
+        day,
+        input: &req.body,
+        input_path: None,
+        day_args: &day_args,
+        timeout: None,
+    };
+    let mut solution = Solution {
+        day: day.name[3..].parse().unwrap_or_default(),
+        answers: BTreeMap::new(),
+        unproven: BTreeSet::new(),
+        elapsed_ms: 0.0,
+        cached: true,
+    };
+    for run_part in runs(day, None) {
+        // whatever the first part took comes out of the second's time
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Response::error(504, DayFailed::TimedOut(started.elapsed()));
+        }
+        job.timeout = Some(left);
+        match job.solve(run_part, cache, reuse) {
+            Ok((entry, cached)) => {
+                solution.answers.extend(entry.answers);
+                solution.unproven.extend(entry.unproven);
+                solution.elapsed_ms += entry.elapsed.as_secs_f64() * 1000.0;
+                solution.cached &= cached;
+            }
+            Err(e) => {
+                return match e.downcast_ref::<DayFailed>() {
+                    Some(DayFailed::TimedOut(_)) => {
+                        Response::error(504, DayFailed::TimedOut(started.elapsed()))
+                    }
+                    Some(DayFailed::Failed(stderr)) => {
+                        Response::error(422, format!("{} failed: {}", day.name, summarize(stderr)))
+                    }
+                    None => Response::error(500, format!("{e:#}")),
+                }
+            }
+        }
+    }
+    Response {
+        status: 200,
+        body: serde_json::to_string(&solution).unwrap_or_default(),
+    }
+}
+
+fn handle(stream: TcpStream, limits: Limits, cache: &Cache, reuse: bool) -> Result<()> {
+    stream.set_read_timeout(Some(limits.read_timeout))?;
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader) {
+        Ok(req) => respond(req, limits.time_limit, cache, reuse),
+        Err(e) if timed_out(&e) => Response::error(408, "timed out reading the request"),
+        Err(e) => Response::error(400, e),
+    };
+    write_response(reader.into_inner(), response)
+}
+
+/// Whether reading failed because the client took too long.
+fn timed_out(e: &color_eyre::Report) -> bool {
+    e.downcast_ref::<std::io::Error>().is_some_and(|e| {
+        matches!(
+            e.kind(),
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+        )
+    })
+}
+
+fn write_response(mut stream: TcpStream, response: Response) -> Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        422 => "Unprocessable Entity",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let port: u16 = args.parsed_opt("--port")?.unwrap_or(2022);
+    let limits = Limits {
+        time_limit: args
+            .seconds_opt("--time-limit")?
+            .unwrap_or(Duration::from_secs(60)),
+        read_timeout: args
+            .seconds_opt("--read-timeout")?
+            .unwrap_or(Duration::from_secs(10)),
+    };
+    let reuse = !args.flag("--no-cache");
+    let max_connections: usize = args.parsed_opt("--max-connections")?.unwrap_or(8);
+    args.finish()?;
+    let cache = Arc::new(Cache::open()?);
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    // --port 0 picks a free port, so say which one (the tests read this line)
+    println!("listening on http://{}", listener.local_addr()?);
+    std::io::stdout().flush()?;
+    let active = Arc::new(Active::default());
+    for stream in listener.incoming() {
+        let stream = stream?;
+        // the connections after that wait, unaccepted if need be
+        let slot = Slot::take(&active, max_connections);
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let _slot = slot;
+            if let Err(e) = handle(stream, limits, &cache, reuse) {
+                eprintln!("{e:#}");
+            }
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,365 @@
+//! `aoc tui`: browse the days and their inputs, run one and look at what it did.
+//!
+//! Keys: Tab moves between panes, ↑/↓ (or k/j) pick a day or input or scroll the output and
+//! picture panes, PgUp/PgDn scroll faster, Enter or `r` runs the selected day on the selected
+//! input (again), `q` quits.
+//!
+//! Every part runs with its output captured: the answers and timings, whatever the day printed,
+//! and the pictures it drew (through `AOC_RENDER_DIR`) each get a pane of their own.
+
+use crate::{args::Args, run::runs};
+use aoc2022::{
+    days::{Day, DAYS},
+    exec,
+    puzzle::INPUT_VAR,
+    render::RENDER_DIR_VAR,
+};
+use color_eyre::Result;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+/// What one run of a day on an input did.
+struct Report {
+    day: &'static str,
+    input: PathBuf,
+    answers: BTreeMap<u8, String>,
+    /// one line per run: which part, how long, how it ended
+    timings: Vec<String>,
+    output: String,
+    pictures: Vec<(String, String)>,
+}
+
+fn run_day(day: &'static Day, input_path: PathBuf, time_limit: Duration) -> Result<Report> {
+    let input = fs::read(&input_path)?;
+    let mut report = Report {
+        day: day.name,
+        input: input_path.clone(),
+        answers: BTreeMap::new(),
+        timings: vec![],
+        output: String::new(),
+        pictures: vec![],
+    };
+    for run_part in runs(day, None) {
+        let label = match run_part {
+            Some(part) => format!("part {part}"),
+            None => "all parts".to_owned(),
+        };
+        let render_dir = exec::scratch_path("pictures");
+        let mut cmd = day.command(run_part)?;
+        cmd.env(INPUT_VAR, &input_path)
+            .env(RENDER_DIR_VAR, &render_dir);
+        let outcome = exec::run(cmd, &input, Some(time_limit))?;
+        let ended = if outcome.timed_out() {
+            "timed out"
+        } else if outcome.panicked() {
+            "panicked"
+        } else if !outcome.success() {
+            "failed"
+        } else {
+            "ok"
+        };
+        report
+            .timings
+            .push(format!("{label}: {:.2?} {ended}", outcome.elapsed));
+        report.answers.extend(outcome.answers);
+        report.output += &format!("── {label} stdout ──\n{}", outcome.stdout);
+        if !outcome.stderr.is_empty() {
+            report.output += &format!(
+                "── {label} stderr ──\n{}",
+                exec::strip_ansi(&outcome.stderr)
+            );
+        }
+        if let Ok(entries) = fs::read_dir(&render_dir) {
+            let mut names: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+            names.sort();
+            for path in names {
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let name = match run_part {
+                    Some(part) => format!("{name} (part {part})"),
+                    None => name,
+                };
+                report.pictures.push((name, fs::read_to_string(&path)?));
+            }
+            fs::remove_dir_all(&render_dir)?;
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Pane {
+    Days,
+    Inputs,
+    Output,
+    Pictures,
+}
+
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Days => Pane::Inputs,
+            Pane::Inputs => Pane::Output,
+            Pane::Output => Pane::Pictures,
+            Pane::Pictures => Pane::Days,
+        }
+    }
+}
+
+struct App {
+    inputs_dir: PathBuf,
+    time_limit: Duration,
+    focus: Pane,
+    days: ListState,
+    inputs: Vec<PathBuf>,
+    input_list: ListState,
+    output_scroll: u16,
+    picture_scroll: u16,
+    report: Option<Report>,
+    /// the day being run, which the selection can move away from meanwhile
+    running: Option<(&'static str, Receiver<Result<Report>>)>,
+    error: Option<String>,
+}
+
+impl App {
+    fn new(inputs_dir: PathBuf, time_limit: Duration) -> App {
+        let mut app = App {
+            inputs_dir,
+            time_limit,
+            focus: Pane::Days,
+            days: ListState::default().with_selected(Some(0)),
+            inputs: vec![],
+            input_list: ListState::default(),
+            output_scroll: 0,
+            picture_scroll: 0,
+            report: None,
+            running: None,
+            error: None,
+        };
+        app.day_changed();
+        app
+    }
+
+    fn day(&self) -> &'static Day {
+        &DAYS[self.days.selected().unwrap_or(0)]
+    }
+
+    fn day_changed(&mut self) {
+        self.inputs = self.day().inputs(&self.inputs_dir);
+        // most days have an example and the real input; the example is quicker
+        let example = self.inputs.iter().position(|p| p.ends_with("example.txt"));
+        self.input_list
+            .select(example.or((!self.inputs.is_empty()).then_some(0)));
+    }
+
+    fn start_run(&mut self) {
+        if self.running.is_some() {
+            return;
+        }
+        let Some(input) = self.input_list.selected().map(|i| self.inputs[i].clone()) else {
+            self.error = Some(format!("no inputs for {}", self.day().name));
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let (day, time_limit) = (self.day(), self.time_limit);
+        thread::spawn(move || {
+            let _ = tx.send(run_day(day, input, time_limit));
+        });
+        self.running = Some((day.name, rx));
+        self.error = None;
+    }
+
+    fn check_run(&mut self) {
+        let Some((_, rx)) = &self.running else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(color_eyre::eyre::eyre!("run thread died")),
+        };
+        self.running = None;
+        match result {
+            Ok(report) => {
+                self.report = Some(report);
+                self.output_scroll = 0;
+                self.picture_scroll = 0;
+            }
+            Err(e) => self.error = Some(format!("{e:#}")),
+        }
+    }
+
+    fn move_by(&mut self, delta: i32) {
+        let scroll = |pos: u16| (pos as i32 + delta).max(0) as u16;
+        match self.focus {
+            Pane::Days => {
+                let day = self.days.selected().unwrap_or(0) as i32 + delta;
+                self.days
+                    .select(Some(day.clamp(0, DAYS.len() as i32 - 1) as usize));
+                self.day_changed();
+            }
+            Pane::Inputs if !self.inputs.is_empty() => {
+                let input = self.input_list.selected().unwrap_or(0) as i32 + delta;
+                self.input_list
+                    .select(Some(input.clamp(0, self.inputs.len() as i32 - 1) as usize));
+            }
+            Pane::Inputs => {}
+            Pane::Output => self.output_scroll = scroll(self.output_scroll),
+            Pane::Pictures => self.picture_scroll = scroll(self.picture_scroll),
+        }
+    }
+
+    fn block(&self, title: &str, pane: Option<Pane>) -> Block<'static> {
+        let block = Block::bordered().title(format!(" {title} "));
+        if pane == Some(self.focus) {
+            block.border_style(Style::new().fg(Color::Yellow))
+        } else {
+            block
+        }
+    }
+
+    fn summary(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        if let Some(error) = &self.error {
+            lines.push(Line::from(error.clone()).red());
+        }
+        if let Some((name, _)) = &self.running {
+            lines.push(Line::from(format!("running {name}...")).yellow());
+        }
+        if let Some(report) = &self.report {
+            let input = report
+                .input
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            lines.push(Line::from(format!("{} on {input}", report.day)).bold());
+            for (part, answer) in &report.answers {
+                lines.push(Line::from(format!("part {part}: {answer}")));
+            }
+            for timing in &report.timings {
+                lines.push(Line::from(timing.clone()).dim());
+            }
+        }
+        if lines.is_empty() {
+            lines.push(Line::from(
+                "Enter or r runs the selected day, Tab switches pane, q quits",
+            ));
+        }
+        lines
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [left, right] =
+            Layout::horizontal([Constraint::Length(28), Constraint::Min(0)]).areas(frame.area());
+        let [days_area, inputs_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(8)]).areas(left);
+        let summary = self.summary();
+        let [summary_area, output_area, pictures_area] = Layout::vertical([
+            Constraint::Length(summary.len() as u16 + 2),
+            Constraint::Percentage(40),
+            Constraint::Min(0),
+        ])
+        .areas(right);
+
+        let highlight = Style::new().reversed();
+        let days = List::new(DAYS.iter().map(|d| d.name))
+            .block(self.block("days", Some(Pane::Days)))
+            .highlight_style(highlight);
+        frame.render_stateful_widget(days, days_area, &mut self.days);
+        let inputs = List::new(self.inputs.iter().map(|p| {
+            p.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        }))
+        .block(self.block("inputs", Some(Pane::Inputs)))
+        .highlight_style(highlight);
+        frame.render_stateful_widget(inputs, inputs_area, &mut self.input_list);
+
+        frame.render_widget(
+            Paragraph::new(summary).block(self.block("answers", None)),
+            summary_area,
+        );
+        let output = self
+            .report
+            .as_ref()
+            .map(|r| r.output.clone())
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(output)
+                .block(self.block("output", Some(Pane::Output)))
+                .scroll((self.output_scroll, 0)),
+            output_area,
+        );
+        self.draw_pictures(frame, pictures_area);
+    }
+
+    fn draw_pictures(&self, frame: &mut Frame, area: Rect) {
+        let mut text = String::new();
+        for (name, picture) in self.report.iter().flat_map(|r| &r.pictures) {
+            text += &format!("── {name} ──\n{picture}\n");
+        }
+        frame.render_widget(
+            Paragraph::new(text)
+                .block(self.block("pictures", Some(Pane::Pictures)))
+                .scroll((self.picture_scroll, 0)),
+            area,
+        );
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            self.check_run();
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Tab => self.focus = self.focus.next(),
+                KeyCode::BackTab => {
+                    self.focus = self.focus.next().next().next();
+                }
+                KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+                KeyCode::PageUp => self.move_by(-10),
+                KeyCode::PageDown => self.move_by(10),
+                KeyCode::Enter | KeyCode::Char('r') => self.start_run(),
+                _ => {}
+            }
+        }
+    }
+}
+
+pub fn main(mut args: Args) -> Result<()> {
+    let inputs_dir = PathBuf::from(args.opt("--inputs")?.unwrap_or_else(|| "inputs".to_owned()));
+    let time_limit = Duration::from_secs_f64(args.parsed_opt("--time-limit")?.unwrap_or(60.0));
+    args.finish()?;
+    let mut app = App::new(inputs_dir, time_limit);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
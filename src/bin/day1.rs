@@ -1,34 +1,366 @@
-use color_eyre::Result;
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    let mut inventories = Vec::new();
-    let mut curinv: Option<Vec<u32>> = None;
-    for line in std::io::stdin().lines() {
-        let line = line?;
-        if line.is_empty() {
-            if let Some(completed) = curinv.take() {
-                inventories.push(completed);
+use aoc2022::{
+    args::arg_value,
+    check::{self, Problems},
+    Puzzle,
+};
+use color_eyre::{
+    eyre::{bail, eyre},
+    Report, Result,
+};
+use num::rational::Ratio;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+/// What's worth knowing about an elf's inventory, kept instead of the items themselves.
+#[derive(Debug, Clone, Copy)]
+struct Inventory {
+    /// 1-based, in input order
+    elf: usize,
+    total: u64,
+    largest: u32,
+    items: u32,
+}
+
+impl Inventory {
+    fn new(elf: usize) -> Inventory {
+        Inventory {
+            elf,
+            total: 0,
+            largest: 0,
+            items: 0,
+        }
+    }
+
+    fn add(&mut self, calories: u32) {
+        self.total += calories as u64;
+        self.largest = self.largest.max(calories);
+        self.items += 1;
+    }
+
+    fn mean(&self) -> f64 {
+        self.total as f64 / self.items as f64
+    }
+}
+
+/// Descriptive statistics of a list of numbers.
+struct Summary {
+    sorted: Vec<f64>,
+    mean: f64,
+    /// of the whole population, since every elf is in it
+    stddev: f64,
+}
+
+impl Summary {
+    fn of(values: impl Iterator<Item = u64>) -> Option<Summary> {
+        let mut sorted: Vec<f64> = values.map(|v| v as f64).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Some(Summary {
+            sorted,
+            mean,
+            stddev: variance.sqrt(),
+        })
+    }
+
+    /// Interpolating between the values either side, so the median of an even count is the mean
+    /// of the middle two.
+    fn percentile(&self, p: f64) -> f64 {
+        let rank = p / 100.0 * (self.sorted.len() - 1) as f64;
+        let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+        let frac = rank - below as f64;
+        self.sorted[below] + (self.sorted[above] - self.sorted[below]) * frac
+    }
+
+    /// Tukey's fences: anything further than 1.5 interquartile ranges outside the middle half.
+    fn fences(&self) -> (f64, f64) {
+        let (q1, q3) = (self.percentile(25.0), self.percentile(75.0));
+        let iqr = q3 - q1;
+        (q1 - 1.5 * iqr, q3 + 1.5 * iqr)
+    }
+
+    fn row(&self, name: &str) -> String {
+        let mut row = format!("{name:<10}{:>6}", self.sorted.len());
+        for p in [0.0, 10.0, 25.0, 50.0, 75.0, 90.0, 100.0] {
+            row += &format!("{:>10.1}", self.percentile(p));
+        }
+        row + &format!("{:>10.1}{:>10.1}", self.mean, self.stddev)
+    }
+}
+
+/// `bins` equal-width bins from the smallest value to the largest, drawn `width` wide at most.
+fn histogram(summary: &Summary, bins: usize, width: usize) -> String {
+    let (lo, hi) = (summary.sorted[0], *summary.sorted.last().unwrap());
+    let bin_width = ((hi - lo) / bins as f64).max(1.0);
+    let mut counts = vec![0; bins];
+    for v in &summary.sorted {
+        counts[(((v - lo) / bin_width) as usize).min(bins - 1)] += 1;
+    }
+    let most = *counts.iter().max().unwrap();
+    let mut picture = String::new();
+    for (bin, count) in counts.into_iter().enumerate() {
+        let start = lo + bin as f64 * bin_width;
+        let bar = "#".repeat((count * width).div_ceil(most));
+        picture += &format!(
+            "{:>10.0} - {:<10.0}|{bar:<width$} {count}\n",
+            start,
+            start + bin_width
+        );
+    }
+    picture
+}
+
+/// What `--stats` prints: how the totals and item counts are spread, and which elves stand out.
+fn stats_report(inventories: &[Inventory]) -> String {
+    let (Some(totals), Some(items)) = (
+        Summary::of(inventories.iter().map(|inv| inv.total)),
+        Summary::of(inventories.iter().map(|inv| inv.items as u64)),
+    ) else {
+        return "no inventories\n".to_owned();
+    };
+    let mut report = format!("{:<10}{:>6}", "per elf", "count");
+    for heading in [
+        "min", "p10", "p25", "median", "p75", "p90", "max", "mean", "stddev",
+    ] {
+        report += &format!("{heading:>10}");
+    }
+    report += &format!("\n{}\n{}\n", totals.row("calories"), items.row("items"));
+    report += "calories per elf:\n";
+    report += &histogram(&totals, 10, 40);
+    let (low, high) = totals.fences();
+    let outliers: Vec<&Inventory> = inventories
+        .iter()
+        .filter(|inv| (inv.total as f64) < low || (inv.total as f64) > high)
+        .collect();
+    report += &format!(
+        "{} outliers outside {low:.0} - {high:.0} calories\n",
+        outliers.len()
+    );
+    for inv in outliers {
+        report += &format!(
+            "  elf {}: {} calories in {} items\n",
+            inv.elf, inv.total, inv.items
+        );
+    }
+    report
+}
+
+/// Read inventories one at a time, so no more than one is ever in memory.
+struct Inventories<R> {
+    lines: io::Lines<R>,
+    elves: usize,
+}
+
+impl<R: BufRead> Iterator for Inventories<R> {
+    type Item = Result<Inventory>;
+
+    fn next(&mut self) -> Option<Result<Inventory>> {
+        let mut curinv: Option<Inventory> = None;
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+            if line.is_empty() {
+                if curinv.is_some() {
+                    break;
+                }
+                continue;
             }
-            continue;
+            let calories: u32 = match line.parse() {
+                Ok(calories) => calories,
+                Err(e) => {
+                    let elf = self.elves + 1;
+                    return Some(Err(eyre!("elf {elf}: bad calorie count {line:?}: {e}")));
+                }
+            };
+            curinv
+                .get_or_insert_with(|| Inventory::new(self.elves + 1))
+                .add(calories);
         }
-        let calories: u32 = line.parse()?;
-        curinv.get_or_insert_with(Vec::new).push(calories);
+        self.elves += curinv.is_some() as usize;
+        curinv.map(Ok)
     }
-    if let Some(completed) = curinv.take() {
-        inventories.push(completed);
+}
+
+/// What `--by` ranks the elves on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Aggregate {
+    Sum,
+    Max,
+    Count,
+    Mean,
+}
+
+impl Aggregate {
+    /// As a fraction, so that means rank exactly.
+    fn of(self, inv: &Inventory) -> Ratio<u64> {
+        match self {
+            Aggregate::Sum => Ratio::from_integer(inv.total),
+            Aggregate::Max => Ratio::from_integer(inv.largest as u64),
+            Aggregate::Count => Ratio::from_integer(inv.items as u64),
+            Aggregate::Mean => Ratio::new(inv.total, inv.items as u64),
+        }
     }
+}
+
+impl FromStr for Aggregate {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Aggregate> {
+        Ok(match s {
+            "sum" => Aggregate::Sum,
+            "max" => Aggregate::Max,
+            "count" => Aggregate::Count,
+            "mean" => Aggregate::Mean,
+            _ => bail!("unknown aggregate {s:?}, expected sum, max, count or mean"),
+        })
+    }
+}
 
-    eprintln!("Inventories: {:#?}", inventories);
-    let mut sums: Vec<u32> = inventories.iter().map(|x| x.iter().sum()).collect();
-    sums.sort();
-    eprintln!("Sums: {:#?}", sums);
-    let maxsum = sums.last();
-    if let Some(maxsum) = maxsum {
-        println!("max sum: {}", maxsum);
+/// An inventory in a [`TopK`], ordered best first: higher values, then earlier elves.
+struct Ranked {
+    value: Ratio<u64>,
+    inv: Inventory,
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Ranked) -> Ordering {
+        (self.value, Reverse(self.inv.elf)).cmp(&(other.value, Reverse(other.inv.elf)))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Ranked) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Ranked) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+/// The best `k` inventories seen so far, with the worst of them on top of the heap to make way.
+struct TopK {
+    k: usize,
+    by: Aggregate,
+    heap: BinaryHeap<Reverse<Ranked>>,
+}
+
+impl TopK {
+    fn new(k: usize, by: Aggregate) -> TopK {
+        TopK {
+            k,
+            by,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, inv: Inventory) {
+        self.heap.push(Reverse(Ranked {
+            value: self.by.of(&inv),
+            inv,
+        }));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    /// Best first.
+    fn into_sorted(self) -> Vec<Inventory> {
+        // ascending order of the Reverse is best first
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|r| r.0.inv)
+            .collect()
+    }
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let inventories = check::blocks(input);
+    for inventory in &inventories {
+        for &(lnum, line) in inventory {
+            problems.parse::<u32>(lnum, line, "a calorie count");
+        }
+    }
+    if inventories.len() < 3 {
+        problems.input(format!(
+            "{} inventories, the top 3 needs at least 3",
+            inventories.len()
+        ));
+    }
+    problems
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
+    let k: usize = arg_value("--top")?.unwrap_or(3);
+    let by: Aggregate = arg_value("--by")?.unwrap_or(Aggregate::Sum);
+    // the one thing that needs every elf at once, so only when asked for
+    let stats = std::env::args().any(|a| a == "--stats");
+    let mut summaries = vec![];
+    let mut top = TopK::new(k, by);
+    // the answers are always about the top 3 totals, whatever the report is ranked by
+    let mut top3 = TopK::new(3, Aggregate::Sum);
+    let mut inventories = Inventories {
+        lines: io::stdin().lock().lines(),
+        elves: 0,
+    };
+    for inv in inventories.by_ref() {
+        let inv = inv?;
+        top.push(inv);
+        top3.push(inv);
+        if stats {
+            summaries.push(inv);
+        }
+    }
+    let elves = inventories.elves;
+
+    let by_name = format!("{by:?}").to_lowercase();
+    println!("top {k} of {elves} elves by {by_name}:");
+    for inv in top.into_sorted() {
+        println!(
+            "  elf {}: {} calories in {} items, largest {}, mean {:.2}",
+            inv.elf,
+            inv.total,
+            inv.items,
+            inv.largest,
+            inv.mean()
+        );
+    }
+    if stats {
+        print!("{}", stats_report(&summaries));
+    }
+    let top3 = top3.into_sorted();
+    let Some(most) = top3.first() else {
+        bail!("no inventories");
+    };
+    println!("max sum: {}", most.total);
+    puzzle.answer(1, most.total)?;
+    if top3.len() < 3 {
+        bail!("only {elves} elves, the top 3 needs at least 3");
     }
-    let top3sums = &sums[sums.len() - 3..];
-    let top3total: u32 = top3sums.iter().sum();
+    let top3total: u64 = top3.iter().map(|inv| inv.total).sum();
     println!("top3 total: {}", top3total);
+    puzzle.answer(2, top3total)?;
 
     Ok(())
 }
@@ -1,5 +1,9 @@
 use std::collections::VecDeque;
 
+use aoc2022::{
+    check::{self, Problems},
+    render, Puzzle,
+};
 use color_eyre::{
     eyre::{bail, ContextCompat},
     Result,
@@ -81,8 +85,26 @@ impl Machine {
     }
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["noop"] => {}
+            ["addx", param] => {
+                problems.parse::<i32>(lnum, param, "an addx parameter");
+            }
+            _ => problems.at(lnum, format!("unknown instruction {line:?}")),
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let mut machine = Machine::new();
     for line in std::io::stdin().lines() {
         let line = line?;
@@ -91,8 +113,9 @@ fn main() -> Result<()> {
     }
     let mut sigsamples = Vec::new();
     let mut line = String::new();
+    let mut crt = Vec::new();
     while !machine.is_done() {
-        if machine.cycle_counter == 20 || (machine.cycle_counter + 20) % 40 == 0 {
+        if machine.cycle_counter == 20 || (machine.cycle_counter + 20).is_multiple_of(40) {
             let signal = machine.cycle_counter as i32 * machine.xreg;
             sigsamples.push(signal);
         }
@@ -104,16 +127,14 @@ fn main() -> Result<()> {
             line.push('.')
         }
         if line.len() == 40 {
-            println!("> {}  @{}", line, machine.cycle_counter);
-            line.clear();
+            crt.push(std::mem::take(&mut line));
         }
         machine.tick()?;
     }
+    render::show("crt", crt.join("\n"))?;
     println!("final machine state: {:?}", machine);
-    println!(
-        "signal samples, {:?}, total: {}",
-        sigsamples,
-        sigsamples.iter().sum::<i32>()
-    );
+    let sigtotal = sigsamples.iter().sum::<i32>();
+    println!("signal samples, {:?}, total: {}", sigsamples, sigtotal);
+    puzzle.answer(1, sigtotal)?;
     Ok(())
 }
@@ -1,11 +1,18 @@
 use std::collections::VecDeque;
 use std::io::Write;
 
+use aoc2022::{
+    check::{self, Problems},
+    checked,
+    checkpoint::Checkpoint,
+    Puzzle,
+};
 use color_eyre::{
     eyre::{bail, Context, ContextCompat},
     Result,
 };
 use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 enum Operand {
@@ -28,13 +35,95 @@ struct Monkey {
     test_target_false: usize,
 }
 
+/// Where the rounds are up to, for checkpoints.
+#[derive(Serialize, Deserialize)]
+struct Progress {
+    rounds_done: usize,
+    items: Vec<VecDeque<i64>>,
+    activity: Vec<u64>,
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let monkeys = check::blocks(input);
+    for (idx, monkey) in monkeys.iter().enumerate() {
+        let mut seen = [false; 6];
+        for &(lnum, line) in monkey {
+            let line = line.trim();
+            if let Some(rest) = line.strip_prefix("Monkey ") {
+                seen[0] = true;
+                if rest != format!("{idx}:") {
+                    problems.at(lnum, format!("expected 'Monkey {idx}:'"));
+                }
+            } else if let Some(rest) = line.strip_prefix("Starting items: ") {
+                seen[1] = true;
+                for item in rest.split(", ") {
+                    problems.parse::<i64>(lnum, item, "a worry level");
+                }
+            } else if let Some(rest) = line.strip_prefix("Operation: new = old ") {
+                seen[2] = true;
+                match rest.split_whitespace().collect::<Vec<_>>()[..] {
+                    ["*" | "+", "old"] => {}
+                    ["*" | "+", operand] => {
+                        problems.parse::<i64>(lnum, operand, "an operand");
+                    }
+                    _ => problems.at(lnum, format!("unknown operation {rest:?}")),
+                }
+            } else if let Some(rest) = line.strip_prefix("Test: divisible by ") {
+                seen[3] = true;
+                if problems.parse::<i64>(lnum, rest, "a divisor") == Some(0) {
+                    problems.at(lnum, "divisor is zero");
+                }
+            } else if let Some((which, rest)) = line
+                .strip_prefix("If true: throw to monkey ")
+                .map(|rest| (4, rest))
+                .or_else(|| {
+                    line.strip_prefix("If false: throw to monkey ")
+                        .map(|rest| (5, rest))
+                })
+            {
+                seen[which] = true;
+                match problems.parse::<usize>(lnum, rest, "a monkey number") {
+                    Some(target) if target >= monkeys.len() => {
+                        problems.at(lnum, format!("there's no monkey {target}"));
+                    }
+                    Some(target) if target == idx => {
+                        problems.at(lnum, "monkey throws to itself");
+                    }
+                    _ => {}
+                }
+            } else {
+                problems.at(lnum, format!("unexpected line {line:?}"));
+            }
+        }
+        let names = [
+            "Monkey",
+            "Starting items",
+            "Operation",
+            "Test",
+            "If true",
+            "If false",
+        ];
+        for (name, seen) in names.iter().zip(seen) {
+            if !seen {
+                problems.at(monkey[0].0, format!("monkey {idx} has no '{name}' line"));
+            }
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let do_part2 = std::env::args().any(|x| x == "--part2");
     let mut current_monkey: Option<MonkeyBuilder> = None;
     let mut monkeys = Vec::new();
-    for line in std::io::stdin().lines() {
-        let line = line?;
+    let input = std::io::read_to_string(std::io::stdin())?;
+    for line in input.lines() {
         let line = line.trim();
         if current_monkey.is_none() {
             current_monkey = Some(MonkeyBuilder::default());
@@ -76,14 +165,41 @@ fn main() -> Result<()> {
     for m in &monkeys {
         println!("  {:?}", m);
     }
-    let dprod: i64 = monkeys.iter().map(|m| m.divisor).product();
+    let dprod: i64 = monkeys
+        .iter()
+        .try_fold(1, |acc, m| checked::mul("divisor product", acc, m.divisor))?;
     println!("Product of all test divisors: {}", dprod);
     let mut monkey_activity = vec![0; monkeys.len()];
-    for round in 0..(if do_part2 { 10000 } else { 20 }) {
+    let rounds: usize = if do_part2 {
+        puzzle.param_or("part2_rounds", 10000)?
+    } else {
+        puzzle.param_or("part1_rounds", 20)?
+    };
+    let mut checkpoint =
+        Checkpoint::open(if do_part2 { "part2" } else { "part1" }, &input, &puzzle)?;
+    let mut first_round = 0;
+    if let Some(progress) = checkpoint.resume::<Progress>()? {
+        for (monkey, items) in monkeys.iter_mut().zip(progress.items) {
+            monkey.items = items;
+        }
+        monkey_activity = progress.activity;
+        first_round = progress.rounds_done;
+        println!("Resuming after round {first_round}");
+    }
+    for round in first_round..rounds {
         print!("round {}...\r", round + 1);
         std::io::stdout().flush()?;
-        do_round(&mut monkeys, &mut monkey_activity, do_part2, dprod);
+        do_round(&mut monkeys, &mut monkey_activity, do_part2, dprod)
+            .wrap_err_with(|| format!("in round {}", round + 1))?;
+        if checkpoint.due() {
+            checkpoint.save(&Progress {
+                rounds_done: round + 1,
+                items: monkeys.iter().map(|m| m.items.clone()).collect(),
+                activity: monkey_activity.clone(),
+            })?;
+        }
     }
+    checkpoint.done()?;
     println!();
     println!("Items after rounds:");
     print_items(&monkeys);
@@ -91,28 +207,30 @@ fn main() -> Result<()> {
     monkey_activity.sort();
     let monkey_business: u64 = monkey_activity[monkey_activity.len() - 2..]
         .iter()
-        .product();
+        .try_fold(1, |acc, a| checked::mul("monkey business", acc, *a))?;
     println!("Monkey business: {}", monkey_business);
+    puzzle.answer(if do_part2 { 2 } else { 1 }, monkey_business)?;
     Ok(())
 }
 
-fn print_items(monkeys: &Vec<Monkey>) {
+fn print_items(monkeys: &[Monkey]) {
     (0..monkeys.len()).for_each(|idx| {
         println!("Monkey {}: {:?}", idx, monkeys[idx].items);
     });
 }
-fn do_round(monkeys: &mut Vec<Monkey>, activity: &mut [u64], part2: bool, dprod: i64) {
+fn do_round(monkeys: &mut [Monkey], activity: &mut [u64], part2: bool, dprod: i64) -> Result<()> {
     for idx in 0..monkeys.len() {
         while let Some(worry) = monkeys[idx].items.pop_front() {
             activity[idx] += 1;
             //println!("Monkey inspects item with worry level {}", worry);
             let prev_worry = worry;
             let worry = match &monkeys[idx].op {
-                Expr::Sum(Operand::Literal(a)) => prev_worry + a,
-                Expr::Mul(Operand::Literal(a)) => prev_worry * a,
-                Expr::Sum(Operand::Old) => prev_worry + prev_worry,
-                Expr::Mul(Operand::Old) => prev_worry * prev_worry,
-            };
+                Expr::Sum(Operand::Literal(a)) => checked::add("worry", prev_worry, *a),
+                Expr::Mul(Operand::Literal(a)) => checked::mul("worry", prev_worry, *a),
+                Expr::Sum(Operand::Old) => checked::add("worry", prev_worry, prev_worry),
+                Expr::Mul(Operand::Old) => checked::mul("worry", prev_worry, prev_worry),
+            }
+            .wrap_err_with(|| format!("monkey {idx} inspecting an item"))?;
             // boredom / mod
             let worry = if !part2 { worry / 3 } else { worry % dprod };
             // throw
@@ -124,4 +242,5 @@ fn do_round(monkeys: &mut Vec<Monkey>, activity: &mut [u64], part2: bool, dprod:
             monkeys[target].items.push_back(worry);
         }
     }
+    Ok(())
 }
@@ -4,6 +4,11 @@ use std::{
     io::Read,
 };
 
+use aoc2022::{
+    check::{self, Problems},
+    dir::Dir4,
+    Puzzle,
+};
 use color_eyre::{eyre::ContextCompat, Result};
 
 struct Heightmap {
@@ -43,7 +48,7 @@ impl Heightmap {
         let mut end = None;
         for ch in input.chars() {
             let height = match ch {
-                ch if ('a'..='z').contains(&ch) => Some(ch as u8 - b'a'),
+                ch if ch.is_ascii_lowercase() => Some(ch as u8 - b'a'),
                 'S' => {
                     start = Some(raw_pos_to_pos(stride, idx));
                     Some(0)
@@ -72,26 +77,15 @@ impl Heightmap {
         let rawp = x + y * self.stride;
         self.heights[rawp]
     }
-    fn up(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
-        Some((x, y.checked_sub(1)?))
-    }
-    fn left(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
-        Some((x.checked_sub(1)?, y))
-    }
-    fn down(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
-        (y < (self.heights.len() / self.stride) - 1).then_some((x, y + 1))
-    }
-    fn right(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
-        (x < self.stride - 1).then_some((x + 1, y))
+    fn step(&self, pos: (usize, usize), dir: Dir4) -> Option<(usize, usize)> {
+        let (x, y) = dir.go(pos)?;
+        (x < self.stride && y < self.heights.len() / self.stride).then_some((x, y))
     }
     fn adjacents(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
-        let adj = [
-            self.up(pos),
-            self.right(pos),
-            self.down(pos),
-            self.left(pos),
-        ];
-        adj.into_iter().flatten().collect()
+        [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left]
+            .into_iter()
+            .filter_map(|dir| self.step(pos, dir))
+            .collect()
     }
     fn climbable(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
         let curheight = self.height_at(pos);
@@ -158,19 +152,39 @@ impl Heightmap {
     }
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    problems.grid(check::lines(input), |c| {
+        c.is_ascii_lowercase() || c == 'S' || c == 'E'
+    });
+    for marker in ['S', 'E'] {
+        let count = input.matches(marker).count();
+        if count != 1 {
+            problems.input(format!("expected one {marker} marker, found {count}"));
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let mut buf = String::new();
     std::io::stdin().read_to_string(&mut buf)?;
     let hm = Heightmap::from_str(&buf)?;
     println!("map:\n{}", hm);
     let part1 = hm.search(hm.start, Heightmap::climbable, Heightmap::is_end);
     println!("Part 1 (shortest path S->E): {:?}", part1);
+    puzzle.answer(1, part1.context("no path S->E")?)?;
     let part2 = hm.search(
         hm.end,
         Heightmap::climbable_from,
         Heightmap::is_zero_elevation,
     );
     println!("Part 2 (shortest path E->zero height): {:?}", part2);
+    puzzle.answer(2, part2.context("no path E->zero height")?)?;
     Ok(())
 }
@@ -1,5 +1,10 @@
 use std::cmp::{Ord, Ordering};
 
+use aoc2022::{
+    check::{self, Problems},
+    explain::Explanation,
+    Puzzle,
+};
 use color_eyre::{
     eyre::{bail, ContextCompat},
     Result,
@@ -21,14 +26,12 @@ impl Value {
                 digit @ '0'..='9' => {
                     num.push(digit);
                 }
-                ',' => {
-                    if !num.is_empty() {
-                        stack
-                            .last_mut()
-                            .context("working list")?
-                            .push(Num(num.parse()?));
-                        num.clear();
-                    }
+                ',' if !num.is_empty() => {
+                    stack
+                        .last_mut()
+                        .context("working list")?
+                        .push(Num(num.parse()?));
+                    num.clear();
                 }
                 '[' => {
                     stack.push(vec![]);
@@ -72,28 +75,80 @@ impl PartialOrd for Value {
     }
 }
 
+fn check_packet(problems: &mut Problems, lnum: usize, line: &str) {
+    if !line.starts_with('[') {
+        problems.at(lnum, "packet doesn't start with [");
+    }
+    let mut depth = 0;
+    for (col, c) in line.chars().enumerate() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => problems.at(lnum, format!("unmatched ] at column {}", col + 1)),
+            ']' => {
+                depth -= 1;
+                if depth == 0 && col + 1 < line.len() {
+                    problems.at(
+                        lnum,
+                        format!("trailing characters after column {}", col + 1),
+                    );
+                    return;
+                }
+            }
+            '0'..='9' | ',' => {}
+            c => problems.at(lnum, format!("unexpected {c:?} at column {}", col + 1)),
+        }
+    }
+    if depth > 0 {
+        problems.at(lnum, format!("{depth} unclosed ["));
+    }
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for pair in check::blocks(input) {
+        if pair.len() != 2 {
+            problems.at(pair[0].0, format!("pair has {} packets", pair.len()));
+        }
+        for (lnum, line) in pair {
+            check_packet(&mut problems, lnum, line);
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = std::io::read_to_string(std::io::stdin())?;
     let mut pairs = vec![];
+    let mut pair_text = vec![];
     for linepair in input.trim().split("\n\n") {
         let mut lit = linepair.lines();
-        let a = Value::from_str(lit.next().context("pair_left")?)?;
-        let b = Value::from_str(lit.next().context("pair_right")?)?;
-        pairs.push((a, b));
+        let left = lit.next().context("pair_left")?;
+        let right = lit.next().context("pair_right")?;
+        pairs.push((Value::from_str(left)?, Value::from_str(right)?));
+        pair_text.push((left, right));
     }
+    let mut explanation = Explanation::new(1);
     let mut mismatch_indices = vec![];
     for (i, (a, b)) in pairs.iter().enumerate() {
         let cmp = a.cmp(b);
         println!("Pair {}: l {:?} r", i + 1, cmp);
         if cmp == Ordering::Less {
             mismatch_indices.push(i + 1);
+            let (left, right) = pair_text[i];
+            explanation.step(format!("pair {}: {left} < {right}", i + 1));
         }
     }
-    println!(
-        "Mismatch index sum: {}",
-        mismatch_indices.iter().sum::<usize>()
-    );
+    let mismatch_sum = mismatch_indices.iter().sum::<usize>();
+    println!("Mismatch index sum: {}", mismatch_sum);
+    let terms: Vec<String> = mismatch_indices.iter().map(|i| i.to_string()).collect();
+    explanation.step(format!("{} = {mismatch_sum}", terms.join(" + ")));
+    explanation.show(mismatch_sum)?;
+    puzzle.answer(1, mismatch_sum)?;
     let mut all_packets: Vec<Value> = input
         .lines()
         .filter(|s| !s.is_empty())
@@ -113,7 +168,15 @@ fn main() -> Result<()> {
             bidx = Some(i + 1);
         }
     }
-    let key = aidx.context("a idx")? * bidx.context("b idx")?;
+    let (aidx, bidx) = (aidx.context("a idx")?, bidx.context("b idx")?);
+    let key = aidx * bidx;
     println!("Decoder key: {}", key);
+    let mut explanation = Explanation::new(2);
+    explanation.step(format!("sorted {} packets including the dividers", all_packets.len()));
+    explanation.step(format!("[[2]] is packet {aidx}, after {} smaller ones", aidx - 1));
+    explanation.step(format!("[[6]] is packet {bidx}, after {} smaller ones", bidx - 1));
+    explanation.step(format!("{aidx} * {bidx} = {key}"));
+    explanation.show(key)?;
+    puzzle.answer(2, key)?;
     Ok(())
 }
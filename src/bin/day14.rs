@@ -1,16 +1,24 @@
+use aoc2022::{
+    check::{self, Problems},
+    events::{Record, Recorder, Replay},
+    history::{History, Reversible, Rewind},
+    render,
+    simulation::{Runner, Simulation},
+    Puzzle,
+};
 use color_eyre::{
     eyre::{bail, eyre},
     Report, Result,
 };
-
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     fmt::{Debug, Display, Write},
     io,
     str::FromStr,
 };
 
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 struct Pos {
     x: isize,
     y: isize,
@@ -93,6 +101,10 @@ struct Sandbox {
     tiles: HashMap<Pos, Tile>,
     activesand: Option<Pos>,
     floor_y: Option<isize>,
+    /// sand below this falls forever
+    bottom: isize,
+    /// the source is covered
+    blocked: bool,
 }
 
 impl Debug for Sandbox {
@@ -172,8 +184,108 @@ impl Sandbox {
     }
 }
 
+impl Simulation for Sandbox {
+    /// a grain of sand came to rest here
+    type Event = Pos;
+
+    fn step(&mut self) -> Option<Pos> {
+        let falling = self.activesand;
+        self.blocked = self.tick_sand();
+        match (falling, self.activesand) {
+            (Some(pos), None) => Some(pos),
+            _ => None,
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.blocked || self.activesand.is_some_and(|active| active.y > self.bottom)
+    }
+}
+
+impl Reversible for Sandbox {
+    type Cell = Pos;
+
+    fn undo(&mut self, &pos: &Pos) {
+        self.tiles.remove(&pos);
+        self.blocked = false;
+    }
+
+    fn redo(&mut self, &pos: &Pos) {
+        self.tiles.insert(pos, Tile::SandResting);
+    }
+
+    /// where the sand is resting
+    fn cells(&self) -> BTreeSet<Pos> {
+        self.tiles
+            .iter()
+            .filter(|(_, &t)| t == Tile::SandResting)
+            .map(|(&pos, _)| pos)
+            .collect()
+    }
+}
+
+/// What `--record` logs: the rocks, then each grain of sand as it comes to rest, then in part 1
+/// the grain that was falling past the rocks when the run stopped.
+#[derive(Serialize, Deserialize)]
+enum Event {
+    Start { rocks: Vec<Pos>, floor_y: Option<isize> },
+    Settled(Pos),
+    Fell(Pos),
+}
+
+/// Rebuild the sandbox from a `--record` log, as of the requested grain.
+fn replay_log(replay: &Replay) -> Result<()> {
+    let mut sandbox = Sandbox::default();
+    for Record { event, .. } in replay.events()? {
+        match event {
+            Event::Start { rocks, floor_y } => {
+                for pos in rocks {
+                    sandbox.tiles.insert(pos, Tile::Rock);
+                }
+                sandbox.floor_y = floor_y;
+            }
+            Event::Settled(pos) => {
+                sandbox.tiles.insert(pos, Tile::SandResting);
+            }
+            Event::Fell(pos) => {
+                sandbox.tiles.insert(pos, Tile::SandFalling);
+            }
+        }
+    }
+    replay.show(format!("{:?}", sandbox))
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        let mut points = vec![];
+        for point in line.split(" -> ") {
+            match point.parse::<Pos>() {
+                Ok(pos) => points.push(pos),
+                Err(_) => problems.at(lnum, format!("expected x,y, got {point:?}")),
+            }
+        }
+        for pair in points.windows(2) {
+            if pair[0].x != pair[1].x && pair[0].y != pair[1].y {
+                problems.at(
+                    lnum,
+                    format!("diagonal segment {:?} -> {:?}", pair[0], pair[1]),
+                );
+            }
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    if let Some(replay) = Replay::requested()? {
+        return replay_log(&replay);
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let do_part2 = std::env::var("PART2").is_ok();
     let paths: Vec<Vec<Pos>> = input.lines().map(parse_path).collect::<Result<_>>()?;
@@ -181,30 +293,51 @@ fn main() -> Result<()> {
     for p in &paths {
         sandbox.fill_rock_path(p)?;
     }
-    let mut maxy = sandbox.tiles.keys().map(|p| p.y).max().expect("max y");
+    sandbox.bottom = sandbox.tiles.keys().map(|p| p.y).max().expect("max y");
     if do_part2 {
-        sandbox.floor_y = Some(maxy + 2);
-        maxy += 2;
+        sandbox.floor_y = Some(sandbox.bottom + 2);
+        sandbox.bottom += 2;
     }
-    for step in 1.. {
-        let blocked = sandbox.tick_sand();
-
-        let stop = blocked
-            || if let Some(active) = sandbox.activesand {
-                active.y > maxy
-            } else {
-                false
-            };
-        if stop {
-            let rsu = sandbox
-                .tiles
-                .values()
-                .filter(|t| **t == Tile::SandResting)
-                .count();
-            println!("Sandbox tick {}: {:?}", step, sandbox);
-            println!("{} sand units came to rest.", rsu);
-            break;
+    let mut recorder = Recorder::open()?;
+    let mut rocks: Vec<Pos> = sandbox.tiles.keys().copied().collect();
+    rocks.sort_by_key(|p| (p.y, p.x));
+    recorder.record(
+        0,
+        Event::Start {
+            rocks,
+            floor_y: sandbox.floor_y,
+        },
+    )?;
+    let rewind = Rewind::requested()?;
+    let mut history = History::new();
+    let mut settled = 0;
+    let step = Runner::new()
+        .on_event(|_, _, &pos| {
+            settled += 1;
+            if rewind.is_some() {
+                history.record(pos);
+            }
+            recorder.record(settled, Event::Settled(pos))
+        })
+        .run(&mut sandbox)?;
+    if let Some(falling) = sandbox.activesand {
+        recorder.record(settled + 1, Event::Fell(falling))?;
+    }
+    let rsu = sandbox
+        .tiles
+        .values()
+        .filter(|t| **t == Tile::SandResting)
+        .count();
+    println!("Sandbox tick {}:", step);
+    render::show("sandbox", format!("{:?}", sandbox))?;
+    println!("{} sand units came to rest.", rsu);
+    puzzle.answer(if do_part2 { 2 } else { 1 }, rsu)?;
+    if let Some(rewind) = rewind {
+        // the grain that fell into the abyss isn't part of any step
+        if let Some(falling) = sandbox.activesand.take() {
+            sandbox.tiles.remove(&falling);
         }
+        rewind.prompt(&mut sandbox, &mut history, |sandbox| format!("{sandbox:?}"))?;
     }
     Ok(())
 }
@@ -1,5 +1,11 @@
+use aoc2022::{
+    check::{self, Problems},
+    checked, impls,
+    interval::IntervalSet,
+    Puzzle,
+};
 use color_eyre::{
-    eyre::{bail, eyre},
+    eyre::{bail, eyre, Context},
     Result,
 };
 use std::{collections::HashSet, io};
@@ -40,7 +46,7 @@ fn beaconsless_cells_on_row(row: isize, sensors: &[Sensor]) -> usize {
         .max()
         .expect("empty sensor list");
     let xrange =
-        min_x_sensor.location.0 - maxrad as isize..max_x_sensor.location.0 + maxrad as isize;
+        min_x_sensor.location.0 - maxrad as isize..=max_x_sensor.location.0 + maxrad as isize;
     for x in xrange {
         let test_loc = Pos(x, row);
         if beacons.contains(&test_loc) {
@@ -56,6 +62,36 @@ fn beaconsless_cells_on_row(row: isize, sensors: &[Sensor]) -> usize {
     tbl
 }
 
+/// The stretches of `row` that are within some sensor's radius.
+fn row_coverage(row: isize, sensors: &[Sensor]) -> IntervalSet<isize> {
+    sensors
+        .iter()
+        .map(|s| {
+            // an empty range if the row is out of reach
+            let reach = s.beacon_radius() as isize - (s.location.1 - row).abs();
+            s.location.0 - reach..=s.location.0 + reach
+        })
+        .collect()
+}
+
+/// The same, by merging the stretches of the row each sensor covers instead of trying every cell.
+fn beaconless_by_intervals(row: isize, sensors: &[Sensor]) -> usize {
+    let covered = row_coverage(row, sensors).len();
+    // a beacon on the row is always inside its own sensor's stretch
+    let beacons: HashSet<Pos> = sensors
+        .iter()
+        .map(|s| s.nearest_beacon)
+        .filter(|b| b.1 == row)
+        .collect();
+    covered as usize - beacons.len()
+}
+
+type Beaconless = fn(isize, &[Sensor]) -> usize;
+const IMPLS: [(&str, Beaconless); 2] = [
+    ("intervals", beaconless_by_intervals),
+    ("brute", beaconsless_cells_on_row),
+];
+
 fn beacon_find(sensors: &[Sensor], minpos: Pos, maxpos: Pos) -> Result<Pos> {
     for y in minpos.1..maxpos.1 {
         let mut x = minpos.0;
@@ -84,8 +120,27 @@ fn beacon_find(sensors: &[Sensor], minpos: Pos, maxpos: Pos) -> Result<Pos> {
     bail!("No beacon in area");
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let sensor_re = regex::Regex::new(
+        r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$",
+    )
+    .expect("sensor regex");
+    for (lnum, line) in check::lines(input) {
+        if !sensor_re.is_match(line) {
+            problems.at(lnum, format!("not a sensor report: {line:?}"));
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let beaconless = impls::chosen(&IMPLS)?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let sensor_re = regex::Regex::new(
         r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)",
@@ -103,21 +158,22 @@ fn main() -> Result<()> {
             Ok(sens)
         })
         .collect::<Result<_>>()?;
-    let tune = |pos: Pos| pos.0 * 4000000 + pos.1;
-    if sensors[0].location.0 == 2 {
-        let ex = beaconsless_cells_on_row(10, &sensors);
-        println!("Beaconless cells on row 10 (Example input): {}", ex);
-        let beacon = beacon_find(&sensors, Pos(0, 0), Pos(20, 20))?;
-        println!(
-            "(Example) beacon found: {:?}, answer: {}",
-            beacon,
-            tune(beacon)
-        );
-    } else {
-        let part1 = beaconsless_cells_on_row(2000000, &sensors);
-        println!("Beaconless cells on row 2000000 (Part1): {}", part1);
-        let beacon = beacon_find(&sensors, Pos(0, 0), Pos(4000000, 4000000))?;
-        println!("Beacon found: {:?}, answer: {}", beacon, tune(beacon));
-    }
+    let row: isize = puzzle.param_or("row", 2000000)?;
+    let search_max: isize = puzzle.param_or("search_max", 4000000)?;
+    let tune = |pos: Pos| -> Result<isize> {
+        checked::add(
+            "tuning frequency",
+            checked::mul("tuning frequency", pos.0, 4000000)?,
+            pos.1,
+        )
+        .wrap_err_with(|| format!("for beacon at {pos:?}"))
+    };
+    let part1 = beaconless(row, &sensors);
+    println!("Beaconless cells on row {} (Part1): {}", row, part1);
+    puzzle.answer(1, part1)?;
+    let beacon = beacon_find(&sensors, Pos(0, 0), Pos(search_max, search_max))?;
+    let tuning = tune(beacon)?;
+    println!("Beacon found: {:?}, answer: {}", beacon, tuning);
+    puzzle.answer(2, tuning)?;
     Ok(())
 }
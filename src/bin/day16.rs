@@ -1,3 +1,9 @@
+use aoc2022::{
+    check::{self, Problems},
+    explain::Explanation,
+    search::Search,
+    Puzzle,
+};
 use color_eyre::{eyre::eyre, Result};
 use std::{
     collections::{HashMap, HashSet, VecDeque},
@@ -134,7 +140,65 @@ impl<'a> State<'a> {
     }
 }
 
-fn do_part1<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
+/// Replay the chosen actions minute by minute for `--explain`: where everyone went, and how
+/// much each valve released once opened.
+fn explain_plan<'a>(
+    valvemap: &'a Valvemap<'a>,
+    part: u8,
+    actors: &[&str],
+    plan: &[Vec<Action<'a>>],
+    best_pressure: u32,
+) -> Result<()> {
+    let mut explanation = Explanation::new(part);
+    let mut at = vec!["AA"; actors.len()];
+    let mut opened: Vec<(&str, usize)> = vec![];
+    let mut released: HashMap<&str, u32> = HashMap::new();
+    explanation.section("timeline");
+    for (minute, actions) in (1..).zip(plan) {
+        let mut tick = 0;
+        for (valve, _) in &opened {
+            let rate = valvemap.map[*valve].flowrate;
+            *released.entry(valve).or_default() += rate;
+            tick += rate;
+        }
+        let mut did = vec![];
+        for (idx, (who, action)) in actors.iter().zip(actions).enumerate() {
+            did.push(match *action {
+                Action::Wait => format!("{who} wait"),
+                Action::Open(_) if opened.iter().any(|(v, _)| *v == at[idx]) => {
+                    format!("{who} open {} again (already open)", at[idx])
+                }
+                Action::Open(_) => {
+                    opened.push((at[idx], minute));
+                    format!("{who} open {} (rate {})", at[idx], valvemap.map[at[idx]].flowrate)
+                }
+                Action::MoveToward(dest) => {
+                    at[idx] = valvemap.path_toward(at[idx], dest);
+                    if at[idx] == dest {
+                        format!("{who} move to {dest}")
+                    } else {
+                        format!("{who} move to {} toward {dest}", at[idx])
+                    }
+                }
+            });
+        }
+        explanation.step(format!("minute {minute}: {}, releasing {tick}", did.join(", ")));
+    }
+    explanation.section("released");
+    for (valve, minute) in &opened {
+        explanation.step(format!(
+            "{valve}: rate {} open after minute {minute}, {} in total",
+            valvemap.map[*valve].flowrate,
+            released.get(valve).unwrap_or(&0)
+        ));
+    }
+    explanation.step(format!("total {}", released.values().sum::<u32>()));
+    explanation.show(best_pressure)
+}
+
+/// The most pressure you can release alone, and whether the search finished within
+/// `--time-budget`.
+fn do_part1<'a>(valvemap: &'a Valvemap<'a>) -> Result<(u32, bool)> {
     let mut expstack = Vec::new();
     expstack.push(State {
         time_elapsed: 0,
@@ -147,7 +211,11 @@ fn do_part1<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
     let mut best_so_far: HashMap<u32, u32> = HashMap::new();
     let mut best_pressure = 0;
     let mut best_state = None;
+    let mut search = Search::new("part 1")?;
     while let Some(curstate) = expstack.pop() {
+        if !search.expand(expstack.len()) {
+            break;
+        }
         {
             let bpe = best_so_far.entry(curstate.time_elapsed).or_default();
             if curstate.pressure_released > *bpe {
@@ -158,11 +226,13 @@ fn do_part1<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
                     .get(&curstate.time_elapsed.saturating_sub(1))
                     .unwrap_or(&0)
             {
+                search.prune();
                 continue;
             }
         }
         if curstate.time_elapsed <= 30 && curstate.pressure_released > best_pressure {
             best_pressure = curstate.pressure_released;
+            search.improved(best_pressure);
             best_state = Some(curstate.clone())
         }
         let actions = curstate.actions(&valvemap.map);
@@ -171,13 +241,16 @@ fn do_part1<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
         }
     }
     println!("Part 1 best pressure: {:?}", best_pressure);
+    let complete = search.finish();
     if let Some(best_state) = best_state {
         let history = best_state.history.reverse();
         let acts: Vec<&Action<'_>> = history.iter().collect();
         println!("{:?}", acts);
+        let plan: Vec<Vec<Action>> = history.iter().map(|a| vec![*a]).collect();
+        explain_plan(valvemap, 1, &["you"], &plan, best_pressure)?;
     }
 
-    Ok(())
+    Ok((best_pressure, complete))
 }
 
 #[derive(Clone, Debug)]
@@ -294,7 +367,9 @@ impl<'a> StateWithElephant<'a> {
     }
 }
 
-fn do_part2<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
+/// The most pressure you and the elephant can release, and whether the search finished within
+/// `--time-budget`.
+fn do_part2<'a>(valvemap: &'a Valvemap<'a>) -> Result<(u32, bool)> {
     let mut expstack = Vec::new();
     expstack.push(StateWithElephant {
         time_elapsed: 0,
@@ -309,7 +384,11 @@ fn do_part2<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
     let mut best_so_far: HashMap<u32, u32> = HashMap::new();
     let mut best_pressure = 0;
     let mut best_state = None;
+    let mut search = Search::new("part 2")?;
     while let Some(curstate) = expstack.pop() {
+        if !search.expand(expstack.len()) {
+            break;
+        }
         {
             let bpe = best_so_far.entry(curstate.time_elapsed).or_default();
             if curstate.pressure_released > *bpe {
@@ -320,11 +399,13 @@ fn do_part2<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
                     .get(&curstate.time_elapsed.saturating_sub(1))
                     .unwrap_or(&0)
             {
+                search.prune();
                 continue;
             }
         }
         if curstate.time_elapsed <= 26 && curstate.pressure_released > best_pressure {
             best_pressure = curstate.pressure_released;
+            search.improved(best_pressure);
             best_state = Some(curstate.clone())
         }
         let actions = curstate.actions(&valvemap.map);
@@ -333,17 +414,59 @@ fn do_part2<'a>(valvemap: &'a Valvemap<'a>) -> Result<()> {
         }
     }
     println!("Part 2 best pressure: {:?}", best_pressure);
+    let complete = search.finish();
     if let Some(best_state) = best_state {
         let history = best_state.history.reverse();
         let acts: Vec<&(Action<'_>, Action<'_>)> = history.iter().collect();
         println!("{:?}", acts);
+        let plan: Vec<Vec<Action>> = history.iter().map(|(m, e)| vec![*m, *e]).collect();
+        explain_plan(valvemap, 2, &["you", "elephant"], &plan, best_pressure)?;
     }
 
-    Ok(())
+    Ok((best_pressure, complete))
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let valve_re =
+        regex::Regex::new(r"^Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (.*)$")
+            .expect("valve regex");
+    let mut defined = HashMap::new();
+    let mut tunnels = vec![];
+    for (lnum, line) in check::lines(input) {
+        let Some(caps) = valve_re.captures(line) else {
+            problems.at(lnum, format!("not a valve description: {line:?}"));
+            continue;
+        };
+        let name = caps.get(1).unwrap().as_str();
+        if let Some(first) = defined.insert(name, lnum) {
+            problems.at(
+                lnum,
+                format!("valve {name} already defined on line {first}"),
+            );
+        }
+        problems.parse::<u32>(lnum, &caps[2], "a flow rate");
+        for exit in caps.get(3).unwrap().as_str().split(", ") {
+            tunnels.push((lnum, exit));
+        }
+    }
+    for (lnum, exit) in tunnels {
+        if !defined.contains_key(exit) {
+            problems.at(lnum, format!("tunnel to undefined valve {exit:?}"));
+        }
+    }
+    if !defined.contains_key("AA") {
+        problems.input("no valve AA to start from");
+    }
+    problems
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let linere =
         regex::Regex::new(r"Valve (.+) has flow rate=(\d+); tunnels? leads? to valves? (.*)")?;
@@ -360,7 +483,9 @@ fn main() -> Result<()> {
         valvemap.insert(src, Valve { flowrate, exits });
     }
     let valvemap = Valvemap::from(valvemap);
-    do_part1(&valvemap)?;
-    do_part2(&valvemap)?;
+    let (part1, complete) = do_part1(&valvemap)?;
+    puzzle.answer_searched(1, part1, complete)?;
+    let (part2, complete) = do_part2(&valvemap)?;
+    puzzle.answer_searched(2, part2, complete)?;
     Ok(())
 }
@@ -1,10 +1,19 @@
+use aoc2022::{
+    check::{self, Problems},
+    checkpoint::Checkpoint,
+    cycle::CycleFinder,
+    events::{Record, Recorder, Replay},
+    render,
+    simulation::{Runner, Simulation},
+    Puzzle,
+};
 use color_eyre::Result;
 use core::panic;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
     fmt::{Display, Write},
     io,
-    ops::{Add, Neg, Sub},
+    ops::{Add, ControlFlow, Neg, Sub},
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -25,7 +34,6 @@ impl Rock {
         assert!(!loc.0.is_negative());
         assert!(!loc.1.is_negative());
         let rawloc = loc.1 as usize * self.w + loc.0 as usize;
-        let rawloc = rawloc;
         if self.occupancy.len() > rawloc {
             self.occupancy[rawloc]
         } else {
@@ -130,20 +138,7 @@ impl Chamber {
                     if nloc.1 + rock.h as isize > 0
                         || (rock.blocked(iloc) && self.blocked(nloc + iloc))
                     {
-                        let top = loc.1 - rock.h as isize;
-                        let newmax = (top.neg() * CHAMBER_WIDTH) as usize;
-                        // fill
-                        self.occupancy
-                            .resize(self.occupancy.len().max(newmax), false);
-                        for iy in 0..rock.h {
-                            for ix in 0..rock.w {
-                                let riloc = Loc(ix as isize, iy as isize);
-                                if rock.blocked(riloc) {
-                                    self.block(loc + riloc);
-                                }
-                            }
-                        }
-                        self.falling_rock = None;
+                        self.rest(&rock, loc);
                         return;
                     }
                 }
@@ -151,6 +146,22 @@ impl Chamber {
             self.falling_rock = Some((rock, nloc));
         }
     }
+    fn rest(&mut self, rock: &Rock, loc: Loc) {
+        let top = loc.1 - rock.h as isize;
+        let newmax = (top.neg() * CHAMBER_WIDTH) as usize;
+        // fill
+        self.occupancy
+            .resize(self.occupancy.len().max(newmax), false);
+        for iy in 0..rock.h {
+            for ix in 0..rock.w {
+                let riloc = Loc(ix as isize, iy as isize);
+                if rock.blocked(riloc) {
+                    self.block(loc + riloc);
+                }
+            }
+        }
+        self.falling_rock = None;
+    }
     fn stack_height(&self) -> isize {
         let my = (self.occupancy.len() as isize / CHAMBER_WIDTH).neg();
         for y in my..0 {
@@ -234,20 +245,56 @@ impl Display for Chamber {
     }
 }
 
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    let input = io::read_to_string(io::stdin())?;
-    let pseq: Vec<Push> = input
-        .trim()
-        .chars()
-        .map(|c| match c {
-            '<' => Push::Left,
-            '>' => Push::Right,
-            c => panic!("unexpected input char '{}'", c),
-        })
-        .collect();
+/// What decides how the stack grows from here: the shape of its surface, the next jet and
+/// the next rock.
+type State = ([usize; CHAMBER_WIDTH as usize], usize, usize);
+
+/// Everything the main loop knows between rocks, for checkpoints.
+#[derive(Serialize, Deserialize)]
+struct Progress {
+    /// one bitmask per row of the chamber, bottom up
+    rows: Vec<u8>,
+    ridx: usize,
+    pidx: usize,
+    stopped: usize,
+    /// the state and stack height after each rock so far
+    seen: Vec<(State, i64)>,
+}
+
+impl Chamber {
+    fn rows(&self) -> Vec<u8> {
+        self.occupancy
+            .chunks(CHAMBER_WIDTH as usize)
+            .map(|row| row.iter().rev().fold(0, |bits, &b| bits << 1 | b as u8))
+            .collect()
+    }
+
+    fn from_rows(rows: &[u8]) -> Chamber {
+        let occupancy = rows
+            .iter()
+            .flat_map(|bits| (0..CHAMBER_WIDTH).map(move |x| bits & (1 << x) != 0))
+            .collect();
+        Chamber {
+            falling_rock: None,
+            occupancy,
+        }
+    }
+}
 
-    let rocks: Vec<Rock> = vec![
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let lines: Vec<_> = check::lines(input.trim()).collect();
+    if lines.len() != 1 {
+        problems.input(format!("expected one line of jets, got {}", lines.len()));
+    }
+    for (lnum, line) in lines {
+        problems.chars(lnum, line, |c| c == '<' || c == '>');
+    }
+    problems
+}
+
+fn rock_shapes() -> Vec<Rock> {
+    vec![
         "####",
         ".#.
          ###
@@ -264,68 +311,158 @@ fn main() -> Result<()> {
     ]
     .into_iter()
     .map(Rock::from)
-    .collect();
+    .collect()
+}
 
+/// What `--record` logs: each rock as it comes to rest, numbered by how many have stopped.
+#[derive(Serialize, Deserialize)]
+struct Rested {
+    rock: usize,
+    x: isize,
+    y: isize,
+}
+
+/// The chamber with rocks falling into it one after another, one jet of gas per step.
+struct Tower {
+    chamber: Chamber,
+    rocks: Vec<Rock>,
+    jets: Vec<Push>,
+    /// the next rock to fall
+    ridx: usize,
+    /// the next jet to push it
+    pidx: usize,
+    stopped: usize,
+}
+
+impl Tower {
+    fn state(&self) -> State {
+        (self.chamber.floordepth(), self.pidx, self.ridx)
+    }
+}
+
+impl Simulation for Tower {
+    type Event = Rested;
+
+    fn step(&mut self) -> Option<Rested> {
+        // the next rock only appears once the observers have seen the last one land
+        if self.chamber.falling_rock.is_none() {
+            self.chamber.spawn_rock(self.rocks[self.ridx].clone());
+        }
+        self.chamber.push_rock(self.jets[self.pidx]);
+        self.pidx = (self.pidx + 1) % self.jets.len();
+        let falling = self.chamber.falling_rock.as_ref().map(|(_, loc)| *loc);
+        self.chamber.drop_rock();
+        if self.chamber.falling_rock.is_some() {
+            return None;
+        }
+        let Loc(x, y) = falling?;
+        let rested = Rested {
+            rock: self.ridx,
+            x,
+            y,
+        };
+        self.stopped += 1;
+        self.ridx = (self.ridx + 1) % self.rocks.len();
+        Some(rested)
+    }
+}
+
+/// Rebuild the chamber from a `--record` log, as of the requested rock.
+fn replay_log(replay: &Replay) -> Result<()> {
+    let rocks = rock_shapes();
     let mut chamber = Chamber::default();
-    chamber.spawn_rock(rocks[0].clone());
-    let mut ridx = 0;
-    let mut pidx = 0;
-    let mut stopped: usize = 0;
-    println!("{chamber}");
-    let mut lsh = chamber.stack_height();
-    let mut steps = HashSet::new();
-    let mut ccount = false;
-    let mut cinc: usize = 0;
-    let mut clen = 0;
+    for Record { event, .. } in replay.events::<Rested>()? {
+        chamber.rest(&rocks[event.rock], Loc(event.x, event.y));
+    }
+    replay.show(&chamber)
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    if let Some(replay) = Replay::requested()? {
+        return replay_log(&replay);
+    }
+    let puzzle = Puzzle::load()?;
+    let input = io::read_to_string(io::stdin())?;
+    let pseq: Vec<Push> = input
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '<' => Push::Left,
+            '>' => Push::Right,
+            c => panic!("unexpected input char '{}'", c),
+        })
+        .collect();
+
+    let mut recorder = Recorder::open()?;
+
+    let mut tower = Tower {
+        chamber: Chamber::default(),
+        rocks: rock_shapes(),
+        jets: pseq,
+        ridx: 0,
+        pidx: 0,
+        stopped: 0,
+    };
+    // trust a repeat once it has come round a second time
+    let mut finder = CycleFinder::new(1);
+    let mut checkpoint = Checkpoint::open("rocks", &input, &puzzle)?;
+    if let Some(progress) = checkpoint.resume::<Progress>()? {
+        tower.chamber = Chamber::from_rows(&progress.rows);
+        tower.ridx = progress.ridx;
+        tower.pidx = progress.pidx;
+        tower.stopped = progress.stopped;
+        for (state, height) in progress.seen {
+            finder.step(state, height);
+        }
+        println!("resuming after {} rocks stopped", tower.stopped);
+    } else {
+        finder.step(tower.state(), 0);
+    }
     let mut cycle = None;
-    let mut bh = 0;
-    loop {
-        chamber.push_rock(pseq[pidx]);
-        pidx = (pidx + 1) % pseq.len();
-        //println!("{chamber}");
-        chamber.drop_rock();
-        //println!("{chamber}");
-        if chamber.falling_rock.is_none() {
-            stopped += 1;
-            let sh = chamber.stack_height();
-            let linc = sh - lsh;
-            lsh = sh;
-            if ccount {
-                cinc += linc as usize;
-                clen += 1;
+    Runner::new()
+        .on_event(|tower: &Tower, _, rested| {
+            recorder.record(tower.stopped as u64, rested)?;
+            if tower.stopped == 10 {
+                render::show("chamber-10", &tower.chamber)?;
             }
-            let fd = chamber.floordepth();
-            if cycle.is_none() {
-                let ck = (fd, pidx, ridx);
-                if !steps.insert(ck) {
-                    println!("Found cycle beginning with {ck:?}");
-                    if ccount {
-                        println!("Cycle height/len: {cinc} {clen}");
-                        cycle = Some((cinc, clen));
-                        let sl = 1000000000000 - stopped ;
-                        let ccount = sl / clen;
-                        stopped  += ccount * clen;
-                        bh = ccount * cinc;
-                        println!("bump height: {}", bh);
-                    } else {
-                        steps.clear();
-                        steps.insert(ck);
-                        ccount = true;
-                    }
-                };
+            Ok(())
+        })
+        .observe(|tower, _, rested| {
+            if rested.is_none() {
+                return Ok(ControlFlow::Continue(()));
             }
-            if stopped == 2022 || stopped == 1000000000000 {
-                println!(
-                    "{stopped} rocks stopped, stack height {sh}, last increase {linc}"
-                );
-                println!("bump height {} total height {}", bh, sh as usize + bh);
-                if stopped == 1000000000000 {
-                    break;
-                }
+            if let Some(found) = finder.step(tower.state(), tower.chamber.stack_height() as i64) {
+                cycle = Some(found.clone());
+                return Ok(ControlFlow::Break(()));
             }
-            ridx = (ridx + 1) % rocks.len();
-            chamber.spawn_rock(rocks[ridx].clone());
-        }
+            if checkpoint.due() {
+                checkpoint.save(&Progress {
+                    rows: tower.chamber.rows(),
+                    ridx: tower.ridx,
+                    pidx: tower.pidx,
+                    stopped: tower.stopped,
+                    seen: finder.steps().map(|(&state, height)| (state, height)).collect(),
+                })?;
+            }
+            Ok(ControlFlow::Continue(()))
+        })
+        .run(&mut tower)?;
+    let cycle = cycle.expect("rocks only stop falling once a cycle is found");
+    checkpoint.done()?;
+    println!(
+        "Found cycle after {} rocks: every {} rocks the stack grows by {}",
+        cycle.start,
+        cycle.period,
+        cycle.delta()
+    );
+    for (part, rocks) in [(1, 2022), (2, 1000000000000)] {
+        let height = cycle.at(rocks);
+        println!("{rocks} rocks stopped, stack height {height}");
+        puzzle.answer(part, height)?;
     }
     Ok(())
 }
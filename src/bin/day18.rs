@@ -1,3 +1,7 @@
+use aoc2022::{
+    check::{self, Problems},
+    impls, Puzzle,
+};
 use color_eyre::Result;
 use std::{
     collections::{hash_map, HashMap, HashSet},
@@ -30,42 +34,24 @@ impl Loc3 {
     }
 }
 
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    let input = io::read_to_string(io::stdin())?;
-    let cubelocs: Vec<Loc3> = input
-        .lines()
-        .map(|l| {
-            let mut xyz = l.splitn(3, ',');
-            Ok(Loc3(
-                xyz.next().expect("expected comma").parse()?,
-                xyz.next().expect("expected comma").parse()?,
-                xyz.next().expect("expected comma").parse()?,
-            ))
-        })
-        .collect::<Result<_>>()?;
-    let cubeset: HashSet<Loc3> = HashSet::from_iter(cubelocs.iter().copied());
-    let mut unconnected_sides = 0;
-    for cube in cubeset.iter() {
-        for adj in cube.adjacents() {
-            if !cubeset.contains(&adj) {
-                unconnected_sides += 1;
-            }
-        }
-    }
-    println!("P1 unconnected sides: {unconnected_sides}");
-
-    let mins = cubelocs
+/// The smallest and largest coordinates of any cube, separately in each direction.
+fn bounds(cubes: &HashSet<Loc3>) -> (Loc3, Loc3) {
+    let mins = cubes
         .iter()
         .copied()
         .reduce(|a, b| Loc3(a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)))
         .unwrap();
-    let maxs = cubelocs
+    let maxs = cubes
         .iter()
         .copied()
         .reduce(|a, b| Loc3(a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)))
         .unwrap();
+    (mins, maxs)
+}
 
+/// Faces whose neighbouring air can get out of the bounding box, with a BFS from each one.
+fn exterior_by_bfs(cubeset: &HashSet<Loc3>) -> usize {
+    let (mins, maxs) = bounds(cubeset);
     let oob = |loc: Loc3| {
         loc.0 < mins.0
             || loc.1 < mins.1
@@ -91,7 +77,84 @@ fn main() -> Result<()> {
             }
         }
     }
+    exterior_surface
+}
+
+/// The same with one flood fill of the air in a box a size bigger than the droplet, counting
+/// the faces it runs into.
+fn exterior_by_flood(cubeset: &HashSet<Loc3>) -> usize {
+    let (mins, maxs) = bounds(cubeset);
+    let (lo, hi) = (mins + Loc3(-1, -1, -1), maxs + Loc3(1, 1, 1));
+    let inbox = |l: Loc3| {
+        (lo.0..=hi.0).contains(&l.0) && (lo.1..=hi.1).contains(&l.1) && (lo.2..=hi.2).contains(&l.2)
+    };
+    let mut seen = HashSet::from([lo]);
+    let mut todo = vec![lo];
+    let mut faces = 0;
+    while let Some(air) = todo.pop() {
+        for adj in air.adjacents() {
+            if cubeset.contains(&adj) {
+                faces += 1;
+            } else if inbox(adj) && seen.insert(adj) {
+                todo.push(adj);
+            }
+        }
+    }
+    faces
+}
+
+type Exterior = fn(&HashSet<Loc3>) -> usize;
+const IMPLS: [(&str, Exterior); 2] = [("bfs", exterior_by_bfs), ("flood", exterior_by_flood)];
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        let coords: Vec<&str> = line.split(',').collect();
+        if coords.len() != 3 {
+            problems.at(lnum, format!("expected x,y,z, got {line:?}"));
+            continue;
+        }
+        for coord in coords {
+            problems.parse::<isize>(lnum, coord, "a coordinate");
+        }
+    }
+    problems
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let exterior = impls::chosen(&IMPLS)?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
+    let input = io::read_to_string(io::stdin())?;
+    let cubelocs: Vec<Loc3> = input
+        .lines()
+        .map(|l| {
+            let mut xyz = l.splitn(3, ',');
+            Ok(Loc3(
+                xyz.next().expect("expected comma").parse()?,
+                xyz.next().expect("expected comma").parse()?,
+                xyz.next().expect("expected comma").parse()?,
+            ))
+        })
+        .collect::<Result<_>>()?;
+    let cubeset: HashSet<Loc3> = HashSet::from_iter(cubelocs.iter().copied());
+    let mut unconnected_sides = 0;
+    for cube in cubeset.iter() {
+        for adj in cube.adjacents() {
+            if !cubeset.contains(&adj) {
+                unconnected_sides += 1;
+            }
+        }
+    }
+    println!("P1 unconnected sides: {unconnected_sides}");
+    puzzle.answer(1, unconnected_sides)?;
+
+    let exterior_surface = exterior(&cubeset);
     println!("P2 exterior surface sides: {}", exterior_surface);
+    puzzle.answer(2, exterior_surface)?;
 
     Ok(())
 }
@@ -1,7 +1,21 @@
+use aoc2022::{
+    check::{self, Problems},
+    checkpoint::Checkpoint,
+    explain::{self, Explanation},
+    search::Search,
+    Puzzle,
+};
 use color_eyre::Result;
 use rayon::prelude::*;
 use regex::Regex;
-use std::{collections::HashSet, hash::Hash, io, time::Instant};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    hash::Hash,
+    io,
+    sync::Mutex,
+    time::Instant,
+};
 
 #[derive(Debug, Clone, Copy)]
 struct Blueprint {
@@ -28,7 +42,7 @@ impl Blueprint {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 enum Material {
     Ore,
     Clay,
@@ -36,7 +50,7 @@ enum Material {
     Geode,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 enum Action {
     Wait,
     BuildRobot(Material),
@@ -130,8 +144,60 @@ impl State {
     }
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let bpre = Regex::new(r"^Blueprint (\d+): (.+)$").expect("blueprint regex");
+    let rulere =
+        Regex::new(r"^Each (\w+) robot costs (\d+) (\w+)( and (\d+) (\w+))?$").expect("rule regex");
+    let expected = [
+        ("ore", &["ore"][..]),
+        ("clay", &["ore"]),
+        ("obsidian", &["ore", "clay"]),
+        ("geode", &["ore", "obsidian"]),
+    ];
+    for (lnum, line) in check::lines(input) {
+        let Some(bpcap) = bpre.captures(line) else {
+            problems.at(lnum, format!("not a blueprint: {line:?}"));
+            continue;
+        };
+        let rules: Vec<&str> = bpcap[2]
+            .split('.')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .collect();
+        if rules.len() != expected.len() {
+            problems.at(lnum, format!("expected 4 robot rules, got {}", rules.len()));
+        }
+        for (rule, (robot, costs)) in rules.iter().zip(expected) {
+            let Some(rcap) = rulere.captures(rule) else {
+                problems.at(lnum, format!("not a robot rule: {rule:?}"));
+                continue;
+            };
+            let got: Vec<&str> = [rcap.get(3), rcap.get(6)]
+                .into_iter()
+                .flatten()
+                .map(|m| m.as_str())
+                .collect();
+            if &rcap[1] != robot || got != costs {
+                problems.at(
+                    lnum,
+                    format!(
+                        "expected the {robot} robot to cost {}, got {rule:?}",
+                        costs.join(" and ")
+                    ),
+                );
+            }
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let bpre = Regex::new(r"Blueprint (\d+): (.+)")?;
     let rulere = Regex::new(r"Each (\w+) robot costs (\d+) (\w+)( and (\d+) (\w+))?.\s*")?;
     let input = io::read_to_string(io::stdin())?;
@@ -164,42 +230,113 @@ fn main() -> Result<()> {
         });
     }
     println!("Checking {} blueprints...", blueprints.len());
+    // only keep track of the build decisions if they're going to be shown
+    let track = explain::requested();
     let p1start = Instant::now();
-    let total_qlv: usize = blueprints
+    let scored: Vec<(Blueprint, u16, Vec<Action>, bool)> = blueprints
         .clone()
         .into_par_iter()
         .map(|bp| {
-            let geodes = score_blueprint(bp, 24);
+            let (geodes, decisions, complete) = score_blueprint(bp, 24, track)?;
             let qlv = geodes * bp.bpid;
             println!("bp{} 24min score={geodes}, qlv={}", bp.bpid, qlv);
-            qlv as usize
+            Ok((bp, geodes, decisions, complete))
         })
+        .collect::<Result<_>>()?;
+    let total_qlv: usize = scored
+        .iter()
+        .map(|(bp, geodes, _, _)| (geodes * bp.bpid) as usize)
         .sum();
     let p1time = p1start.elapsed();
     println!("{p1time:?} Total qlv: {total_qlv}\n");
+    let mut explanation = Explanation::new(1);
+    for (bp, geodes, decisions, _) in &scored {
+        explanation.section(format!(
+            "blueprint {}: {geodes} geodes, quality level {}",
+            bp.bpid,
+            geodes * bp.bpid
+        ));
+        explain_decisions(&mut explanation, bp, decisions);
+    }
+    explanation.show(total_qlv)?;
+    let complete = scored.iter().all(|(_, _, _, complete)| *complete);
+    puzzle.answer_searched(1, total_qlv, complete)?;
 
     let p2start = Instant::now();
-    let p2ans: usize = blueprints
+    // each blueprint takes a while at 32 minutes, so checkpoint them as they finish
+    let checkpoint = Checkpoint::open("part2", &input, &puzzle)?;
+    let resumed: BTreeMap<u16, (u16, Vec<Action>)> = checkpoint.resume()?.unwrap_or_default();
+    let finished = Mutex::new((checkpoint, resumed.clone()));
+    let scored: Vec<(Blueprint, u16, Vec<Action>, bool)> = blueprints
         .into_par_iter()
         .take(3)
         .map(|bp| {
-            let geodes = score_blueprint(bp, 32);
+            if let Some((geodes, decisions)) = resumed.get(&bp.bpid) {
+                println!("bp{} 32min score={geodes} (from checkpoint)", bp.bpid);
+                return Ok((bp, *geodes, decisions.clone(), true));
+            }
+            let (geodes, decisions, complete) = score_blueprint(bp, 32, track)?;
+            if complete {
+                let mut finished = finished.lock().unwrap();
+                let (checkpoint, scores) = &mut *finished;
+                scores.insert(bp.bpid, (geodes, decisions.clone()));
+                checkpoint.save(scores)?;
+            }
             println!("bp{} 32min score={geodes}", bp.bpid);
-            geodes as usize
+            Ok((bp, geodes, decisions, complete))
         })
+        .collect::<Result<_>>()?;
+    finished.into_inner().unwrap().0.done()?;
+    let p2ans: usize = scored
+        .iter()
+        .map(|(_, geodes, _, _)| *geodes as usize)
         .product();
     let p2time = p2start.elapsed();
     println!("{p2time:?} Top3 product: {p2ans}");
+    let mut explanation = Explanation::new(2);
+    for (bp, geodes, decisions, _) in &scored {
+        explanation.section(format!("blueprint {}: {geodes} geodes", bp.bpid));
+        explain_decisions(&mut explanation, bp, decisions);
+    }
+    explanation.show(p2ans)?;
+    let complete = scored.iter().all(|(_, _, _, complete)| *complete);
+    puzzle.answer_searched(2, p2ans, complete)?;
     Ok(())
 }
 
-fn score_blueprint(bp: Blueprint, time_limit: u8) -> u16 {
-    let mut states = vec![State::new()];
-    let mut best_final: Option<State> = None;
+/// Replay the robots built along the way for `--explain`.
+fn explain_decisions(explanation: &mut Explanation, bp: &Blueprint, decisions: &[Action]) {
+    let mut state = State::new();
+    for action in decisions {
+        state = state.do_action(*action, bp);
+        if let Action::BuildRobot(material) = action {
+            explanation.step(format!(
+                "minute {}: build {} robot, leaving {} ore, {} clay, {} obsidian, {} geodes",
+                state.time_elapsed,
+                format!("{material:?}").to_lowercase(),
+                state.ore_held,
+                state.clay_held,
+                state.obsidian_held,
+                state.geode_held,
+            ));
+        }
+    }
+}
+
+/// The most geodes `bp` can open in `time_limit` minutes, if `track` is set the actions that
+/// got there, and whether the search finished within `--time-budget`.
+fn score_blueprint(bp: Blueprint, time_limit: u8, track: bool) -> Result<(u16, Vec<Action>, bool)> {
+    let mut states = vec![(State::new(), rpds::List::new())];
+    let mut best_final: Option<(State, rpds::List<Action>)> = None;
     let mut seen = HashSet::new();
     let mut best_at_time = vec![0; time_limit as usize];
-    while let Some(state) = states.pop() {
+    let mut search = Search::new(format!("blueprint {} ({time_limit} minutes)", bp.bpid))?;
+    while let Some((state, history)) = states.pop() {
+        if !search.expand(states.len()) {
+            break;
+        }
         if seen.contains(&state) {
+            search.prune();
             continue;
         } else {
             seen.insert(state);
@@ -207,6 +344,7 @@ fn score_blueprint(bp: Blueprint, time_limit: u8) -> u16 {
         let time_left = time_limit - state.time_elapsed;
         if time_left > 0 {
             if best_at_time[state.time_elapsed as usize] > state.geode_held {
+                search.prune();
                 continue;
             } else {
                 best_at_time[state.time_elapsed as usize] = state.geode_held
@@ -214,15 +352,30 @@ fn score_blueprint(bp: Blueprint, time_limit: u8) -> u16 {
         }
         if state.time_elapsed < time_limit {
             for nact in state.actions_possible(&bp) {
-                states.push(state.do_action(nact, &bp));
+                let history = if track {
+                    history.push_front(nact)
+                } else {
+                    history.clone()
+                };
+                states.push((state.do_action(nact, &bp), history));
             }
-        } else if let Some(best) = best_final {
+        } else if let Some((best, _)) = &best_final {
             if state.geode_held > best.geode_held {
-                best_final = Some(state)
+                search.improved(state.geode_held);
+                best_final = Some((state, history))
             }
         } else {
-            best_final = Some(state)
+            search.improved(state.geode_held);
+            best_final = Some((state, history))
         }
     }
-    best_final.map(|s| s.geode_held).unwrap_or(0)
+    let complete = search.finish();
+    Ok(match best_final {
+        Some((state, history)) => (
+            state.geode_held,
+            history.reverse().iter().copied().collect(),
+            complete,
+        ),
+        None => (0, vec![], complete),
+    })
 }
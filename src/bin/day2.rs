@@ -1,15 +1,149 @@
+use aoc2022::{
+    args::arg_value,
+    check::{self, Problems},
+    Puzzle,
+};
 use color_eyre::{
-    eyre::{bail, ContextCompat},
+    eyre::{bail, ContextCompat, WrapErr},
     Result,
 };
+use serde::Deserialize;
+use std::{collections::HashSet, fs};
 
-#[derive(Clone, Copy)]
-enum RPSPlay {
-    Rock,
-    Paper,
-    Scissors,
+/// The game the puzzle describes, for when no `rules` param names another.
+const RPS_RULES: &str = include_str!("../../inputs/day2/rules/rps.toml");
+
+/// A game of moves that beat each other, as a rules file (`inputs/day2/rules/*.toml`) lays it
+/// out.
+#[derive(Debug, Deserialize)]
+struct Rules {
+    moves: Vec<MoveRule>,
+    outcomes: OutcomeRules,
+    /// `beats[a][b]`: move `a` beats move `b`
+    #[serde(skip)]
+    beats: Vec<Vec<bool>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MoveRule {
+    name: String,
+    /// the code for it in the first column
+    opponent: String,
+    /// the code for it in the second column, when that's a move
+    response: String,
+    score: u32,
+    beats: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutcomeRules {
+    lose: OutcomeRule,
+    draw: OutcomeRule,
+    win: OutcomeRule,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutcomeRule {
+    /// the codes for it in the second column, when that's an outcome
+    codes: Vec<String>,
+    score: u32,
+}
+
+impl Rules {
+    /// The rules file the `rules` param names, or rock, paper, scissors.
+    fn load(puzzle: &Puzzle) -> Result<Rules> {
+        let (source, text) = match puzzle.param_path("rules") {
+            Some(path) => {
+                let source = path.display().to_string();
+                let text =
+                    fs::read_to_string(&path).wrap_err_with(|| format!("reading {source}"))?;
+                (source, text)
+            }
+            None => ("the built-in rules".to_owned(), RPS_RULES.to_owned()),
+        };
+        let mut rules: Rules =
+            toml::from_str(&text).wrap_err_with(|| format!("parsing {source}"))?;
+        let problems = rules.settle();
+        if !problems.is_empty() {
+            bail!(
+                "inconsistent rules in {source}:\n  {}",
+                problems.join("\n  ")
+            );
+        }
+        Ok(rules)
+    }
+
+    /// Work out who beats whom, returning whatever makes the rules unplayable.
+    fn settle(&mut self) -> Vec<String> {
+        let mut problems = vec![];
+        let n = self.moves.len();
+        let mut seen = [HashSet::new(), HashSet::new(), HashSet::new()];
+        for m in &self.moves {
+            let keys = [
+                ("name", &m.name),
+                ("opponent code", &m.opponent),
+                ("response code", &m.response),
+            ];
+            for (seen, (what, key)) in seen.iter_mut().zip(keys) {
+                if !seen.insert(key) {
+                    problems.push(format!("{what} {key:?} is used twice"));
+                }
+            }
+        }
+        let mut outcome_codes = HashSet::new();
+        for outcome in [RPSOutcome::Lose, RPSOutcome::Draw, RPSOutcome::Win] {
+            for code in &self.outcome(outcome).codes {
+                if !outcome_codes.insert(code) {
+                    problems.push(format!("outcome code {code:?} is used twice"));
+                }
+            }
+        }
+
+        self.beats = vec![vec![false; n]; n];
+        for (a, m) in self.moves.iter().enumerate() {
+            for beaten in &m.beats {
+                match self.moves.iter().position(|other| other.name == *beaten) {
+                    Some(b) if b == a => problems.push(format!("{} beats itself", m.name)),
+                    Some(b) => self.beats[a][b] = true,
+                    None => problems.push(format!("{} beats unknown move {beaten:?}", m.name)),
+                }
+            }
+        }
+        for a in 0..n {
+            for b in a + 1..n {
+                let (x, y) = (&self.moves[a].name, &self.moves[b].name);
+                match (self.beats[a][b], self.beats[b][a]) {
+                    (true, true) => problems.push(format!("{x} and {y} both beat each other")),
+                    (false, false) => problems.push(format!("nothing decides {x} against {y}")),
+                    _ => {}
+                }
+            }
+            // part 2 can ask to win or lose against anything
+            let name = &self.moves[a].name;
+            if !(0..n).any(|b| self.beats[b][a]) {
+                problems.push(format!("nothing beats {name}"));
+            }
+            if !(0..n).any(|b| self.beats[a][b]) {
+                problems.push(format!("{name} beats nothing"));
+            }
+        }
+        problems
+    }
+
+    fn outcome(&self, outcome: RPSOutcome) -> &OutcomeRule {
+        match outcome {
+            RPSOutcome::Lose => &self.outcomes.lose,
+            RPSOutcome::Draw => &self.outcomes.draw,
+            RPSOutcome::Win => &self.outcomes.win,
+        }
+    }
 }
 
+/// A move, by its place in the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RPSPlay(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RPSOutcome {
     Win,
     Lose,
@@ -17,97 +151,392 @@ enum RPSOutcome {
 }
 
 impl RPSOutcome {
-    fn decode(inp: &str) -> Result<RPSOutcome> {
+    fn decode(inp: &str, rules: &Rules) -> Result<RPSOutcome> {
         use RPSOutcome::*;
-        match inp {
-            "X" => Ok(Lose),
-            "Y" => Ok(Draw),
-            "Z" => Ok(Win),
-            _ => bail!("Unknown play"),
-        }
+        [Lose, Draw, Win]
+            .into_iter()
+            .find(|&o| rules.outcome(o).codes.iter().any(|c| c == inp))
+            .with_context(|| format!("Unknown outcome {inp:?}"))
     }
 
-    fn vs(self, opponent: RPSPlay) -> RPSPlay {
-        use RPSOutcome::*;
-        use RPSPlay::*;
-        match (self, opponent) {
-            (Win, Rock) => Paper,
-            (Lose, Rock) => Scissors,
-            (Win, Paper) => Scissors,
-            (Lose, Paper) => Rock,
-            (Win, Scissors) => Rock,
-            (Lose, Scissors) => Paper,
-            (Draw, x) => x,
+    /// What `mine` against `opponent` comes to.
+    fn between(mine: RPSPlay, opponent: RPSPlay, rules: &Rules) -> RPSOutcome {
+        if rules.beats[mine.0][opponent.0] {
+            RPSOutcome::Win
+        } else if rules.beats[opponent.0][mine.0] {
+            RPSOutcome::Lose
+        } else {
+            RPSOutcome::Draw
         }
     }
+
+    /// The move that comes to this against `opponent`: the highest scoring one, when more than
+    /// one does, and the first of those.
+    fn vs(self, opponent: RPSPlay, rules: &Rules) -> RPSPlay {
+        (0..rules.moves.len())
+            .map(RPSPlay)
+            .filter(|&mine| RPSOutcome::between(mine, opponent, rules) == self)
+            .min_by_key(|&mine| std::cmp::Reverse(rules.moves[mine.0].score))
+            .expect("settled rules can always win, lose and draw")
+    }
 }
 
 impl RPSPlay {
-    fn decode_part1(inp: &str) -> Result<RPSPlay> {
-        use RPSPlay::*;
-        match inp {
-            "A" | "X" => Ok(Rock),
-            "B" | "Y" => Ok(Paper),
-            "C" | "Z" => Ok(Scissors),
-            _ => bail!("Unknown play"),
-        }
+    fn decode_opponent(inp: &str, rules: &Rules) -> Result<RPSPlay> {
+        let idx = rules.moves.iter().position(|m| m.opponent == inp);
+        idx.map(RPSPlay)
+            .with_context(|| format!("Unknown play {inp:?}"))
+    }
+
+    fn decode_part1(inp: &str, rules: &Rules) -> Result<RPSPlay> {
+        let idx = rules.moves.iter().position(|m| m.response == inp);
+        idx.map(RPSPlay)
+            .with_context(|| format!("Unknown play {inp:?}"))
     }
 }
 
+#[derive(Clone, Copy)]
 struct RPSRound {
     opponent: RPSPlay,
     mine: RPSPlay,
 }
 
 impl RPSRound {
-    fn score(self) -> u32 {
-        use RPSPlay::*;
-        let base_score = match self.mine {
-            Rock => 1,
-            Paper => 2,
-            Scissors => 3,
-        };
-        let win_score = match (self.mine, self.opponent) {
-            (Rock, Scissors) => 6,
-            (Rock, Paper) => 0,
-            (Paper, Rock) => 6,
-            (Paper, Scissors) => 0,
-            (Scissors, Paper) => 6,
-            (Scissors, Rock) => 0,
-            (_, _) => 3,
-        };
-        base_score + win_score
+    fn outcome(&self, rules: &Rules) -> RPSOutcome {
+        RPSOutcome::between(self.mine, self.opponent, rules)
     }
 
-    fn decode_part1(inp: &str) -> Result<RPSRound> {
+    fn shape_score(&self, rules: &Rules) -> u32 {
+        rules.moves[self.mine.0].score
+    }
+
+    fn outcome_score(&self, rules: &Rules) -> u32 {
+        rules.outcome(self.outcome(rules)).score
+    }
+
+    fn score(&self, rules: &Rules) -> u32 {
+        self.shape_score(rules) + self.outcome_score(rules)
+    }
+
+    fn decode_part1(inp: &str, rules: &Rules) -> Result<RPSRound> {
         let mut splits = inp.split_whitespace();
-        let opponent = RPSPlay::decode_part1(splits.next().context("opponent move")?)?;
-        let mine = RPSPlay::decode_part1(splits.next().context("my move")?)?;
+        let opponent = RPSPlay::decode_opponent(splits.next().context("opponent move")?, rules)?;
+        let mine = RPSPlay::decode_part1(splits.next().context("my move")?, rules)?;
         Ok(RPSRound { opponent, mine })
     }
 
-    fn decode_part2(inp: &str) -> Result<RPSRound> {
+    fn decode_part2(inp: &str, rules: &Rules) -> Result<RPSRound> {
         let mut splits = inp.split_whitespace();
-        let opponent = RPSPlay::decode_part1(splits.next().context("opponent move")?)?;
-        let outcome = RPSOutcome::decode(splits.next().context("intended outcome")?)?;
-        let mine = outcome.vs(opponent);
+        let opponent = RPSPlay::decode_opponent(splits.next().context("opponent move")?, rules)?;
+        let outcome = RPSOutcome::decode(splits.next().context("intended outcome")?, rules)?;
+        let mine = outcome.vs(opponent, rules);
         Ok(RPSRound { opponent, mine })
     }
 }
 
+/// A way of reading the second column, code by code in the order the rules list the moves'
+/// response codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Cipher {
+    Moves(Vec<RPSPlay>),
+    Outcomes(Vec<RPSOutcome>),
+}
+
+/// Every ordering of `0..n`.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut all = vec![];
+    for rest in permutations(n - 1) {
+        for at in 0..n {
+            let mut perm = rest.clone();
+            perm.insert(at, n - 1);
+            all.push(perm);
+        }
+    }
+    all
+}
+
+impl Cipher {
+    /// Each code a different move, every way round, then each outcome at least one code, every
+    /// way round.
+    fn all(rules: &Rules) -> Vec<Cipher> {
+        use RPSOutcome::*;
+        let n = rules.moves.len();
+        let mut all: Vec<Cipher> = permutations(n)
+            .into_iter()
+            .map(|perm| Cipher::Moves(perm.into_iter().map(RPSPlay).collect()))
+            .collect();
+        for mut assignment in 0..3usize.pow(n as u32) {
+            let outcomes: Vec<RPSOutcome> = (0..n)
+                .map(|_| {
+                    let outcome = [Lose, Draw, Win][assignment % 3];
+                    assignment /= 3;
+                    outcome
+                })
+                .collect();
+            if [Lose, Draw, Win].iter().all(|o| outcomes.contains(o)) {
+                all.push(Cipher::Outcomes(outcomes));
+            }
+        }
+        all
+    }
+
+    /// How the rules read the column in `part`, if that's one of these.
+    fn puzzle(part: u8, rules: &Rules) -> Option<Cipher> {
+        match part {
+            1 => Some(Cipher::Moves((0..rules.moves.len()).map(RPSPlay).collect())),
+            _ => rules
+                .moves
+                .iter()
+                .map(|m| RPSOutcome::decode(&m.response, rules).ok())
+                .collect::<Option<_>>()
+                .map(Cipher::Outcomes),
+        }
+    }
+
+    /// What we play against `opponent` for the `code`th response code.
+    fn play(&self, opponent: RPSPlay, code: usize, rules: &Rules) -> RPSPlay {
+        match self {
+            Cipher::Moves(moves) => moves[code],
+            Cipher::Outcomes(outcomes) => outcomes[code].vs(opponent, rules),
+        }
+    }
+
+    fn describe(&self, rules: &Rules) -> String {
+        let meanings: Vec<String> = match self {
+            Cipher::Moves(moves) => moves
+                .iter()
+                .map(|&m| rules.moves[m.0].name.clone())
+                .collect(),
+            Cipher::Outcomes(outcomes) => outcomes
+                .iter()
+                .map(|o| format!("{o:?}").to_lowercase())
+                .collect(),
+        };
+        let pairs: Vec<String> = rules
+            .moves
+            .iter()
+            .zip(meanings)
+            .map(|(m, meaning)| format!("{}={meaning}", m.response))
+            .collect();
+        let kind = match self {
+            Cipher::Moves(_) => "as moves",
+            Cipher::Outcomes(_) => "as outcomes",
+        };
+        format!("{kind} {}", pairs.join(" "))
+    }
+}
+
+/// What `--ciphers` prints: the total under every reading of the second column, from `rounds`,
+/// how many times each (opponent move, response code) pair came up.
+fn cipher_report(rounds: &[Vec<u64>], rules: &Rules) -> String {
+    let mut scored: Vec<(u64, Cipher)> = Cipher::all(rules)
+        .into_iter()
+        .map(|cipher| {
+            let mut total = 0;
+            for (opponent, codes) in rounds.iter().enumerate() {
+                for (code, &count) in codes.iter().enumerate() {
+                    let opponent = RPSPlay(opponent);
+                    let mine = cipher.play(opponent, code, rules);
+                    total += count * RPSRound { opponent, mine }.score(rules) as u64;
+                }
+            }
+            (total, cipher)
+        })
+        .collect();
+    // best first, and the order they were made in among equals
+    scored.sort_by_key(|(total, _)| std::cmp::Reverse(*total));
+    let puzzle = [Cipher::puzzle(1, rules), Cipher::puzzle(2, rules)];
+    let mut report = format!("{} ways to read the second column:\n", scored.len());
+    let width = scored
+        .iter()
+        .map(|(_, cipher)| cipher.describe(rules).len())
+        .max()
+        .unwrap_or(0);
+    for (total, cipher) in &scored {
+        let part = match puzzle.iter().position(|p| p.as_ref() == Some(cipher)) {
+            Some(idx) => format!("  (part {})", idx + 1),
+            None => String::new(),
+        };
+        report += &format!("  {:<width$}{total:>8}{part}\n", cipher.describe(rules));
+    }
+    for (what, (total, cipher)) in [
+        ("best: ", &scored[0]),
+        ("worst:", &scored[scored.len() - 1]),
+    ] {
+        let tied = scored.iter().filter(|(t, _)| t == total).count() - 1;
+        let tied = match tied {
+            0 => String::new(),
+            n => format!(" (tied with {n} more)"),
+        };
+        report += &format!("{what} {} scores {total}{tied}\n", cipher.describe(rules));
+    }
+    report
+}
+
+/// A round of the guide read both ways: the second column as our move, then as the outcome.
+type Reading = (RPSRound, RPSRound);
+
+/// What `--rounds` prints: every round under both readings, then how often each reading wins,
+/// draws, loses and plays each move.
+fn round_report(rounds: &[Reading], rules: &Rules) -> String {
+    let name = |play: RPSPlay| rules.moves[play.0].name.as_str();
+    let outcome = |round: &RPSRound| format!("{:?}", round.outcome(rules)).to_lowercase();
+    let width = rules
+        .moves
+        .iter()
+        .map(|m| m.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let mut report =
+        format!(
+        "{:>6} {:<width$} {:<4} | {:<width$} {:<7} {:>5} {:>7} | {:<width$} {:<7} {:>5} {:>7}\n",
+        "round", "opponent", "code", "as move", "outcome", "shape", "outcome", "as goal", "outcome",
+        "shape", "outcome"
+    );
+    for (n, (p1, p2)) in rounds.iter().enumerate() {
+        report += &format!(
+            "{:>6} {:<width$} {:<4}",
+            n + 1,
+            name(p1.opponent),
+            rules.moves[p1.mine.0].response
+        );
+        for round in [p1, p2] {
+            report += &format!(
+                " | {:<width$} {:<7} {:>5} {:>7}",
+                name(round.mine),
+                outcome(round),
+                round.shape_score(rules),
+                round.outcome_score(rules)
+            );
+        }
+        report.push('\n');
+    }
+
+    let mut header = format!("{:<12}{:>7}{:>7}{:>7}", "", "wins", "draws", "losses");
+    for m in &rules.moves {
+        header += &format!("{:>w$}", m.name, w = m.name.len().max(5) + 2);
+    }
+    report += &format!("{header}{:>8}\n", "score");
+    for (part, reading) in [(1, "as moves"), (2, "as goals")] {
+        let played: Vec<&RPSRound> = rounds
+            .iter()
+            .map(|(p1, p2)| if part == 1 { p1 } else { p2 })
+            .collect();
+        let count = |o: RPSOutcome| played.iter().filter(|r| r.outcome(rules) == o).count();
+        let mut row = format!(
+            "{reading:<12}{:>7}{:>7}{:>7}",
+            count(RPSOutcome::Win),
+            count(RPSOutcome::Draw),
+            count(RPSOutcome::Lose)
+        );
+        for (idx, m) in rules.moves.iter().enumerate() {
+            let times = played.iter().filter(|r| r.mine == RPSPlay(idx)).count();
+            row += &format!("{:>w$}", times, w = m.name.len().max(5) + 2);
+        }
+        let score: u32 = played.iter().map(|r| r.score(rules)).sum();
+        report += &format!("{row}{score:>8}\n");
+    }
+    report
+}
+
+/// The rounds as `--csv` writes them, a line each.
+fn round_csv(rounds: &[Reading], rules: &Rules) -> String {
+    let mut csv = "round,opponent,code".to_owned();
+    for part in [1, 2] {
+        csv += &format!(
+            ",part{part}_move,part{part}_outcome,part{part}_shape_score,part{part}_outcome_score"
+        );
+    }
+    csv.push('\n');
+    for (n, (p1, p2)) in rounds.iter().enumerate() {
+        csv += &format!(
+            "{},{},{}",
+            n + 1,
+            rules.moves[p1.opponent.0].name,
+            rules.moves[p1.mine.0].response
+        );
+        for round in [p1, p2] {
+            csv += &format!(
+                ",{},{},{},{}",
+                rules.moves[round.mine.0].name,
+                format!("{:?}", round.outcome(rules)).to_lowercase(),
+                round.shape_score(rules),
+                round.outcome_score(rules)
+            );
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+fn check_input(input: &str, rules: &Rules) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [opponent, mine] => {
+                if RPSPlay::decode_opponent(opponent, rules).is_err() {
+                    problems.at(lnum, format!("unknown opponent move {opponent:?}"));
+                }
+                if RPSPlay::decode_part1(mine, rules).is_err() {
+                    problems.at(lnum, format!("unknown response {mine:?}"));
+                }
+                if RPSOutcome::decode(mine, rules).is_err() {
+                    problems.at(lnum, format!("unknown outcome {mine:?}"));
+                }
+            }
+            ref fields => problems.at(lnum, format!("expected 2 columns, got {}", fields.len())),
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let puzzle = Puzzle::load()?;
+    let rules = Rules::load(&puzzle)?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?, &rules).report();
+    }
+    let args: Vec<String> = std::env::args().collect();
+    let ciphers = args.iter().any(|a| a == "--ciphers");
+    let report = args.iter().any(|a| a == "--rounds");
+    let csv: Option<String> = arg_value("--csv")?;
+    // only kept when there's a report to make of them
+    let mut readings: Vec<Reading> = vec![];
     let mut part1_score = 0;
     let mut part2_score = 0;
+    let n = rules.moves.len();
+    let mut rounds = vec![vec![0; n]; n];
 
     for line in std::io::stdin().lines() {
         let line = line?;
-        let roundp1 = RPSRound::decode_part1(line.as_str())?;
-        let roundp2 = RPSRound::decode_part2(line.as_str())?;
-        part1_score += roundp1.score();
-        part2_score += roundp2.score();
+        let roundp1 = RPSRound::decode_part1(line.as_str(), &rules)?;
+        let roundp2 = RPSRound::decode_part2(line.as_str(), &rules)?;
+        part1_score += roundp1.score(&rules);
+        part2_score += roundp2.score(&rules);
+        // part 1 reads the code as the move in the same place
+        rounds[roundp1.opponent.0][roundp1.mine.0] += 1;
+        if report || csv.is_some() {
+            readings.push((roundp1, roundp2));
+        }
+    }
+    if report {
+        print!("{}", round_report(&readings, &rules));
+    }
+    if let Some(path) = csv {
+        fs::write(&path, round_csv(&readings, &rules))
+            .wrap_err_with(|| format!("writing {path}"))?;
+    }
+    if ciphers {
+        print!("{}", cipher_report(&rounds, &rules));
     }
     println!("part 1 score {}", part1_score);
     println!("part 2 score {}", part2_score);
+    puzzle.answer(1, part1_score)?;
+    puzzle.answer(2, part2_score)?;
     Ok(())
 }
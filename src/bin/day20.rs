@@ -1,7 +1,21 @@
+use aoc2022::{
+    check::{self, Problems},
+    checked,
+    checkpoint::Checkpoint,
+    Puzzle,
+};
 use color_eyre::Result;
+use serde::{Deserialize, Serialize};
 use std::io;
 
-fn do_moves(original: &Vec<(usize, i64)>, targ: &mut Vec<(usize, i64)>) {
+/// How far part 2's mixing has got, for checkpoints.
+#[derive(Serialize, Deserialize)]
+struct Mixing {
+    rounds_done: usize,
+    order: Vec<(usize, i64)>,
+}
+
+fn do_moves(original: &Vec<(usize, i64)>, targ: &mut Vec<(usize, i64)>, trace: bool) {
     for mover in original {
         let pos = targ
             .iter()
@@ -13,8 +27,7 @@ fn do_moves(original: &Vec<(usize, i64)>, targ: &mut Vec<(usize, i64)>) {
             npos += targ.len() as isize;
         }
         targ.insert(npos as usize, *mover);
-        if targ.len() < 100 {
-            //example:
+        if trace {
             println!("{:?}", targ);
         }
     }
@@ -29,31 +42,67 @@ fn coord(moved: &[(usize, i64)]) -> i64 {
         .sum()
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let mut zeros = 0;
+    for (lnum, line) in check::lines(input) {
+        if problems.parse::<i64>(lnum, line, "a number") == Some(0) {
+            zeros += 1;
+        }
+    }
+    if zeros != 1 {
+        problems.input(format!("expected exactly one 0, found {zeros}"));
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let original: Vec<(usize, i64)> = input
         .lines()
         .map(|l| l.parse().unwrap())
         .enumerate()
         .collect();
+    let trace = puzzle.param_or("trace", false)?;
+    let decryption_key: i64 = puzzle.param_or("decryption_key", 811589153)?;
+    let mix_rounds: usize = puzzle.param_or("mix_rounds", 10)?;
     let mut p1moved = original.clone();
-    if p1moved.len() < 100 {
-        //example:
+    if trace {
         println!("{:?}", p1moved);
     }
-    do_moves(&original, &mut p1moved);
+    do_moves(&original, &mut p1moved, trace);
     println!("p1sum: {}", coord(&p1moved));
+    puzzle.answer(1, coord(&p1moved))?;
 
     let p2o: Vec<(usize, i64)> = original
         .into_iter()
-        .map(|(i, n)| (i, n * 811589153))
-        .collect();
+        .map(|(i, n)| Ok((i, checked::mul("decrypting", n, decryption_key)?)))
+        .collect::<Result<_>>()?;
     let mut p2moved = p2o.clone();
-    for _ in 0..10 {
-        do_moves(&p2o, &mut p2moved);
+    let mut checkpoint = Checkpoint::open("part2", &input, &puzzle)?;
+    let mut first_round = 0;
+    if let Some(mixing) = checkpoint.resume::<Mixing>()? {
+        p2moved = mixing.order;
+        first_round = mixing.rounds_done;
+        println!("resuming after mixing round {first_round}");
+    }
+    for round in first_round..mix_rounds {
+        do_moves(&p2o, &mut p2moved, trace);
+        if checkpoint.due() {
+            checkpoint.save(&Mixing {
+                rounds_done: round + 1,
+                order: p2moved.clone(),
+            })?;
+        }
     }
+    checkpoint.done()?;
     println!("p2sum: {}", coord(&p2moved));
+    puzzle.answer(2, coord(&p2moved))?;
 
     Ok(())
 }
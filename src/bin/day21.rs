@@ -1,4 +1,11 @@
-use color_eyre::Result;
+use aoc2022::{
+    check::{self, Problems},
+    checked, Puzzle,
+};
+use color_eyre::{
+    eyre::{Context, ContextCompat},
+    Result,
+};
 use core::panic;
 use regex::Regex;
 use std::{collections::HashMap, hash::Hash, io};
@@ -38,8 +45,53 @@ enum MonkeyRule {
     Variable,
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let mmre = Regex::new(r"^(\w+): ((\d+)|((\w+) ([-+*/]) (\w+)))$").unwrap();
+    let mut defined = HashMap::new();
+    let mut references = vec![];
+    for (lnum, line) in check::lines(input) {
+        let Some(mlcap) = mmre.captures(line) else {
+            problems.at(lnum, format!("not a monkey job: {line:?}"));
+            continue;
+        };
+        let name = mlcap.get(1).unwrap().as_str();
+        if let Some(first) = defined.insert(name, lnum) {
+            problems.at(
+                lnum,
+                format!("monkey {name} already defined on line {first}"),
+            );
+        }
+        if let Some(shout) = mlcap.get(3) {
+            problems.parse::<i64>(lnum, shout.as_str(), "a number");
+        }
+        if let (Some(lhs), Some(rhs)) = (mlcap.get(5), mlcap.get(7)) {
+            references.push((lnum, lhs.as_str()));
+            references.push((lnum, rhs.as_str()));
+            if name == "root" && &mlcap[6] != "+" {
+                problems.at(lnum, "root's job must be a + (it becomes = in part 2)");
+            }
+        }
+    }
+    for (lnum, name) in references {
+        if !defined.contains_key(name) {
+            problems.at(lnum, format!("undefined monkey {name:?}"));
+        }
+    }
+    for name in ["root", "humn"] {
+        if !defined.contains_key(name) {
+            problems.input(format!("no {name} monkey"));
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let mmre = Regex::new(r"(\w+): ((\d+)|((\w+) (.) (\w+)))").unwrap();
     let mut mmap = MonkeyMap::default();
@@ -72,23 +124,35 @@ fn main() -> Result<()> {
     while results.values().len() < mrules.len() {
         let incompletes: Vec<MonkeyId> = mrules
             .keys()
-            .filter(|p| results.get(*p).is_none())
+            .filter(|p| !results.contains_key(*p))
             .copied()
             .collect();
         for mid in incompletes {
-            if let Some(result) = (|| {
+            let result = (|| -> Result<Option<i64>> {
                 use MonkeyRule::*;
                 let rule = mrules.get(&mid).expect("no rule for monkey");
+                let operands = |r, l| Some((*results.get(r)?, *results.get(l)?));
+                let what = "monkey job";
 
-                match &rule {
+                Ok(match &rule {
                     Shout(v) => Some(*v),
-                    Add(r, l) => Some(results.get(r)? + results.get(l)?),
-                    Sub(r, l) => Some(results.get(r)? - results.get(l)?),
-                    Mul(r, l) => Some(results.get(r)? * results.get(l)?),
-                    Div(r, l) => Some(results.get(r)? / results.get(l)?),
+                    Add(r, l) => operands(r, l)
+                        .map(|(a, b)| checked::add(what, a, b))
+                        .transpose()?,
+                    Sub(r, l) => operands(r, l)
+                        .map(|(a, b)| checked::sub(what, a, b))
+                        .transpose()?,
+                    Mul(r, l) => operands(r, l)
+                        .map(|(a, b)| checked::mul(what, a, b))
+                        .transpose()?,
+                    Div(r, l) => operands(r, l)
+                        .map(|(a, b)| checked::div(what, a, b))
+                        .transpose()?,
                     _ => todo!(),
-                }
-            })() {
+                })
+            })()
+            .wrap_err_with(|| format!("monkey {}", mmap.id_to_name[&mid]))?;
+            if let Some(result) = result {
                 results.insert(mid, result);
             }
         }
@@ -96,6 +160,7 @@ fn main() -> Result<()> {
     let rootresult = results.get(&rootid);
     let humnid = mmap.get_or_insert("humn");
     println!("p1 root monkey result: {rootresult:?}");
+    puzzle.answer(1, rootresult.context("root result")?)?;
 
     if let MonkeyRule::Add(l, r) = mrules[&rootid] {
         mrules.insert(rootid, MonkeyRule::Unify(l, r));
@@ -107,7 +172,7 @@ fn main() -> Result<()> {
     while results.values().len() < mrules.len() {
         let incompletes: Vec<MonkeyId> = mrules
             .keys()
-            .filter(|p| results.get(*p).is_none())
+            .filter(|p| !results.contains_key(*p))
             .copied()
             .collect();
         for mid in incompletes {
@@ -146,6 +211,11 @@ fn main() -> Result<()> {
     }
     let rootresult = results.get(&rootid).unwrap().clone().full_collapse();
     println!("p2 root monkey result: {rootresult:?}");
+    if let Term::Equal(l, r) = &rootresult {
+        if let (Term::Const(humn), Term::Var) | (Term::Var, Term::Const(humn)) = (&**l, &**r) {
+            puzzle.answer(2, humn)?;
+        }
+    }
 
     Ok(())
 }
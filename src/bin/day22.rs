@@ -1,7 +1,13 @@
-use color_eyre::Result;
+use aoc2022::{
+    check::{self, Problems},
+    dir::Dir4,
+    explain::Explanation,
+    Puzzle,
+};
+use color_eyre::{eyre::bail, Report, Result};
 use num::integer::Roots;
 use regex::Regex;
-use std::{collections::HashSet, hash::Hash, io, ops::Add};
+use std::{collections::HashSet, hash::Hash, io, ops::Add, str::FromStr};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct Loc(usize, usize);
@@ -47,10 +53,30 @@ impl Board {
     }
 }
 
+// how the faces of the cube are laid out in the input, the edge mappings are hardcoded per layout
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CubeNet {
+    Example,
+    MyInput,
+}
+
+impl FromStr for CubeNet {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "example" => Ok(CubeNet::Example),
+            "input" => Ok(CubeNet::MyInput),
+            _ => bail!("unknown cube net {s:?}, expected example or input"),
+        }
+    }
+}
+
 struct CubicBoard {
     board: Board,
     sqside: usize,
     corners: [Loc; 6],
+    net: CubeNet,
 }
 
 impl CubicBoard {
@@ -64,16 +90,15 @@ impl CubicBoard {
         println!("No face: {:?}", loc);
         None
     }
-    fn edgemap(&self, srcface: usize, fromdir: Dir) -> Option<(usize, Dir, bool)> {
+    fn edgemap(&self, srcface: usize, fromdir: Dir4) -> Option<(usize, Dir4, bool)> {
 
-        use Dir::*;
-        if self.sqside == 4 {
-            // example input
+        use Dir4::*;
+        if self.net == CubeNet::Example {
             Some(match (srcface, fromdir) {
                 (0, Up) => (1, Down, true),
                 (0, Right) => (5, Left, true),
                 (0, Left) => (2, Down, false),
-                (1, Up) => (0, Up, true),
+                (1, Up) => (0, Down, true),
                 (1, Left) => (5, Up, true),
                 (1, Down) => (4, Up, true),
                 (2, Up) => (0, Right, false),
@@ -81,6 +106,7 @@ impl CubicBoard {
                 (3, Right) => (5, Down, true),
                 (4, Left) => (2, Up, true),
                 (4, Down) => (1, Up, true),
+                (5, Up) => (3, Left, true),
                 (5, Down) => (1, Right, true),
                 (5, Right) => (0, Left, true),
                 _ => None?,
@@ -111,16 +137,8 @@ impl CubicBoard {
             })
         }
     }
-    fn try_advance(&self, from: Loc, dir: Dir) -> Option<(Loc, Dir)> {
-        if let Some(nowrap) = (|| {
-            Some(match dir {
-                Dir::Right if from.0 < self.board.width => Loc(from.0 + 1, from.1),
-                Dir::Down if from.1 < self.board.height => Loc(from.0, from.1 + 1),
-                Dir::Left => Loc(from.0.checked_sub(1)?, from.1),
-                Dir::Up => Loc(from.0, from.1.checked_sub(1)?),
-                _ => None?,
-            })
-        })() {
+    fn try_advance(&self, from: Loc, dir: Dir4) -> Option<(Loc, Dir4)> {
+        if let Some(nowrap) = self.board.step(from, dir) {
             if self.board.open_tiles.contains(&nowrap) {
                 return Some((nowrap, dir));
             }
@@ -130,8 +148,8 @@ impl CubicBoard {
         }
         // walked off an edge
         let edgepos = match dir {
-            Dir::Up | Dir::Down => from.0 % self.sqside,
-            Dir::Right | Dir::Left => from.1 % self.sqside,
+            Dir4::Up | Dir4::Down => from.0 % self.sqside,
+            Dir4::Right | Dir4::Left => from.1 % self.sqside,
         };
         let (to, ndir, flip) = self
             .edgemap(self.facenum(from).unwrap(), dir)
@@ -142,10 +160,10 @@ impl CubicBoard {
             edgepos
         };
         let inner = match ndir {
-            Dir::Right => Loc(0, edgepos),
-            Dir::Down => Loc(edgepos, 0),
-            Dir::Left => Loc(self.sqside - 1, edgepos),
-            Dir::Up => Loc(edgepos, self.sqside - 1),
+            Dir4::Right => Loc(0, edgepos),
+            Dir4::Down => Loc(edgepos, 0),
+            Dir4::Left => Loc(self.sqside - 1, edgepos),
+            Dir4::Up => Loc(edgepos, self.sqside - 1),
         };
         //println!( "moving from face {:?} -> {:?}, {:?} -> {:?}, {:?} -> {:?}", self.facenum(from), to, dir, ndir, from, inner );
         let nloc = self.corners[to] + inner;
@@ -158,16 +176,13 @@ impl CubicBoard {
 }
 
 impl Board {
-    fn try_advance(&self, from: Loc, dir: Dir) -> Option<Loc> {
-        if let Some(nowrap) = (|| {
-            Some(match dir {
-                Dir::Right if from.0 < self.width => Loc(from.0 + 1, from.1),
-                Dir::Down if from.1 < self.height => Loc(from.0, from.1 + 1),
-                Dir::Left => Loc(from.0.checked_sub(1)?, from.1),
-                Dir::Up => Loc(from.0, from.1.checked_sub(1)?),
-                _ => None?,
-            })
-        })() {
+    /// One step from `from`, if that stays within (or just off the bottom right of) the board.
+    fn step(&self, from: Loc, dir: Dir4) -> Option<Loc> {
+        let (x, y) = dir.go((from.0, from.1))?;
+        (x <= self.width && y <= self.height).then_some(Loc(x, y))
+    }
+    fn try_advance(&self, from: Loc, dir: Dir4) -> Option<Loc> {
+        if let Some(nowrap) = self.step(from, dir) {
             if self.open_tiles.contains(&nowrap) {
                 return Some(nowrap);
             }
@@ -178,22 +193,22 @@ impl Board {
         // must be wrapping
         let all_tiles: HashSet<_> = self.open_tiles.union(&self.walls).collect();
         let wrapto = match dir {
-            Dir::Right => all_tiles
+            Dir4::Right => all_tiles
                 .into_iter()
                 .filter(|l| l.1 == from.1)
                 .min_by_key(|l| l.0)
                 .copied(),
-            Dir::Down => all_tiles
+            Dir4::Down => all_tiles
                 .into_iter()
                 .filter(|l| l.0 == from.0)
                 .min_by_key(|l| l.1)
                 .copied(),
-            Dir::Left => all_tiles
+            Dir4::Left => all_tiles
                 .into_iter()
                 .filter(|l| l.1 == from.1)
                 .max_by_key(|l| l.0)
                 .copied(),
-            Dir::Up => all_tiles
+            Dir4::Up => all_tiles
                 .into_iter()
                 .filter(|l| l.0 == from.0)
                 .max_by_key(|l| l.1)
@@ -210,39 +225,55 @@ impl Board {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Dir {
-    Right = 0,
-    Down = 1,
-    Left = 2,
-    Up = 3,
+#[derive(Debug)]
+struct Player {
+    location: Loc,
+    facing: Dir4,
 }
 
-impl Dir {
-    fn turn_right(self) -> Dir {
-        use Dir::*;
-        match self {
-            Up => Right,
-            Right => Down,
-            Down => Left,
-            Left => Up,
-        }
+impl Player {
+    /// rows and columns count from 1, as in the password
+    fn describe(&self) -> String {
+        format!(
+            "row {} column {} facing {:?}",
+            self.location.1 + 1,
+            self.location.0 + 1,
+            self.facing
+        )
     }
-    fn turn_left(self) -> Dir {
-        use Dir::*;
-        match self {
-            Up => Left,
-            Left => Down,
-            Down => Right,
-            Right => Up,
-        }
+
+    fn password(&self) -> usize {
+        1000 * (self.location.1 + 1) + 4 * (self.location.0 + 1) + self.facing.code() as usize
     }
 }
 
-#[derive(Debug)]
-struct Player {
-    location: Loc,
-    facing: Dir,
+fn explain_forward(
+    explanation: &mut Explanation,
+    dist: usize,
+    moved: usize,
+    player: &Player,
+    faces: &[usize],
+) {
+    let mut text = format!("{dist}: to {}", player.describe());
+    if !faces.is_empty() {
+        let faces: Vec<String> = faces.iter().map(|f| f.to_string()).collect();
+        text += &format!(", onto face {}", faces.join(" then "));
+    }
+    if moved < dist {
+        text += &format!(", hit a wall after {moved}");
+    }
+    explanation.step(text);
+}
+
+fn explain_password(explanation: &mut Explanation, player: &Player) {
+    explanation.step(format!(
+        "password: 1000 * {} + 4 * {} + {} ({:?}) = {}",
+        player.location.1 + 1,
+        player.location.0 + 1,
+        player.facing.code(),
+        player.facing,
+        player.password()
+    ));
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -252,8 +283,43 @@ enum Cmd {
     TurnLeft,
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let lines: Vec<(usize, &str)> = check::lines(input).collect();
+    let Some(split) = lines.iter().position(|(_, l)| l.is_empty()) else {
+        problems.input("no blank line between the board and the path");
+        return problems;
+    };
+    let mut tiles = 0;
+    for &(lnum, line) in &lines[..split] {
+        problems.chars(lnum, line, |c| " .#".contains(c));
+        tiles += line.chars().filter(|&c| c != ' ').count();
+    }
+    if !lines[..split].iter().any(|(_, l)| l.contains('.')) {
+        problems.input("board has no open tiles");
+    }
+    let face = tiles / 6;
+    if tiles % 6 != 0 || face.sqrt() * face.sqrt() != face {
+        problems.input(format!("{tiles} tiles can't fold into a cube"));
+    }
+    match &lines[split + 1..] {
+        [(lnum, path)] => {
+            problems.chars(*lnum, path, |c| c.is_ascii_digit() || c == 'R' || c == 'L')
+        }
+        rest => problems.input(format!(
+            "expected one line of path after the board, got {}",
+            rest.len()
+        )),
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let (boardtext, given_path) = input.split_once("\n\n").expect("no separator line");
     let max_x = boardtext.lines().map(str::len).max().unwrap();
@@ -298,39 +364,48 @@ fn main() -> Result<()> {
         .expect("topleft open tile");
     let mut player = Player {
         location: start_loc,
-        facing: Dir::Right,
+        facing: Dir4::Right,
     };
 
     println!("start: {player:?}");
+    let mut explanation = Explanation::new(1);
+    explanation.step(format!("start at {}", player.describe()));
     for cmd in &cmds {
         //println!("{cmd:?}");
         match cmd {
             Cmd::Forward(dist) => {
+                let mut moved = 0;
                 for _ in 0..*dist {
                     if let Some(adv) = board.try_advance(player.location, player.facing) {
                         player.location = adv;
+                        moved += 1;
                         //println!(" move to {adv:?}");
                     } else {
                         //println!(" blocked");
                         break;
                     }
                 }
+                explain_forward(&mut explanation, *dist, moved, &player, &[]);
             }
             Cmd::TurnRight => {
                 player.facing = player.facing.turn_right();
+                explanation.step(format!("R: facing {:?}", player.facing));
                 //println!(" facing: {:?}", player.facing);
             }
             Cmd::TurnLeft => {
                 player.facing = player.facing.turn_left();
+                explanation.step(format!("L: facing {:?}", player.facing));
                 //println!(" facing: {:?}", player.facing);
             }
         }
     }
 
     //println!("end: {player:?}");
-    let passwd =
-        1000 * (player.location.1 + 1) + 4 * (player.location.0 + 1) + player.facing as usize;
+    let passwd = player.password();
     println!("passwd: {passwd}");
+    explain_password(&mut explanation, &player);
+    explanation.show(passwd)?;
+    puzzle.answer(1, passwd)?;
 
     let edgelen = board.square_edge();
     let faces = board.face_corners();
@@ -340,40 +415,55 @@ fn main() -> Result<()> {
         sqside: edgelen,
         corners: faces,
         board,
+        net: puzzle.param_or("cube_net", CubeNet::MyInput)?,
     };
     println!("starting face: {:?}", cubic.facenum(start_loc));
     let mut player = Player {
         location: start_loc,
-        facing: Dir::Right,
+        facing: Dir4::Right,
     };
 
     println!("start: {player:?}");
+    let mut explanation = Explanation::new(2);
+    explanation.step(format!("start at {}", player.describe()));
     for cmd in &cmds {
         //println!("{cmd:?}");
         match cmd {
             Cmd::Forward(dist) => {
+                let mut moved = 0;
+                let mut faces = vec![];
                 for _ in 0..*dist {
                     if let Some(adv) = cubic.try_advance(player.location, player.facing) {
+                        let from_face = cubic.facenum(player.location);
                         (player.location, player.facing) = adv;
+                        moved += 1;
+                        if cubic.facenum(player.location) != from_face {
+                            faces.extend(cubic.facenum(player.location));
+                        }
                         // print!(" -> {adv:?}");
                     } else {
                         //println!(" blocked");
                         break;
                     }
                 }
+                explain_forward(&mut explanation, *dist, moved, &player, &faces);
             }
             Cmd::TurnRight => {
                 player.facing = player.facing.turn_right();
+                explanation.step(format!("R: facing {:?}", player.facing));
                 //println!(" facing: {:?}", player.facing);
             }
             Cmd::TurnLeft => {
                 player.facing = player.facing.turn_left();
+                explanation.step(format!("L: facing {:?}", player.facing));
                 //println!(" facing: {:?}", player.facing);
             }
         }
     }
-    let passwd =
-        1000 * (player.location.1 + 1) + 4 * (player.location.0 + 1) + player.facing as usize;
+    let passwd = player.password();
     println!("passwd: {passwd}");
+    explain_password(&mut explanation, &player);
+    explanation.show(passwd)?;
+    puzzle.answer(2, passwd)?;
     Ok(())
 }
@@ -1,55 +1,39 @@
+use aoc2022::{
+    check::{self, Problems},
+    dir::{Dir4, Dir8},
+    events::{Record, Recorder, Replay},
+    history::{History, Reversible, Rewind},
+    render,
+    simulation::{Runner, Simulation},
+    Puzzle,
+};
 use color_eyre::Result;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     io,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 struct Loc(isize, isize);
 
 impl Loc {
-    fn go(self, dir: Dir) -> Loc {
-        use Dir::*;
-        match dir {
-            N => Loc(self.0, self.1 - 1),
-            S => Loc(self.0, self.1 + 1),
-            E => Loc(self.0 + 1, self.1),
-            W => Loc(self.0 - 1, self.1),
-        }
+    fn go(self, dir: impl Into<Dir8>) -> Loc {
+        let (dx, dy) = dir.into().offset();
+        Loc(self.0 + dx, self.1 + dy)
     }
     fn adjacents(self) -> [Loc; 8] {
-        use Dir::*;
-        [
-            self.go(N).go(E),
-            self.go(N),
-            self.go(N).go(W),
-            self.go(W),
-            self.go(W).go(S),
-            self.go(S),
-            self.go(S).go(E),
-            self.go(E),
-        ]
-    }
-    fn propspace(self, dir: Dir) -> [Loc; 3] {
-        use Dir::*;
-        match dir {
-            N => [self.go(N), self.go(N).go(E), self.go(N).go(W)],
-            S => [self.go(S), self.go(S).go(E), self.go(S).go(W)],
-            E => [self.go(E), self.go(E).go(S), self.go(E).go(N)],
-            W => [self.go(W), self.go(W).go(S), self.go(W).go(N)],
-        }
+        Dir8::ALL.map(|dir| self.go(dir))
+    }
+    /// The three places that have to be empty to move `dir`: straight ahead and either side.
+    fn propspace(self, dir: Dir4) -> [Loc; 3] {
+        let dir = Dir8::from(dir);
+        [self.go(dir), self.go(dir.turn_right()), self.go(dir.turn_left())]
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Dir {
-    N,
-    S,
-    E,
-    W,
-}
-
-fn print_grid(elflocs: &HashSet<Loc>) {
+fn render_grid(elflocs: &HashSet<Loc>) -> String {
+    let mut out = String::new();
     let mut minloc = Loc(isize::MAX, isize::MAX);
     let mut maxloc = Loc(isize::MIN, isize::MIN);
     for loc in elflocs {
@@ -61,13 +45,14 @@ fn print_grid(elflocs: &HashSet<Loc>) {
     for y in minloc.1..=maxloc.1 {
         for x in minloc.0..=maxloc.0 {
             if elflocs.contains(&Loc(x, y)) {
-                print!("#");
+                out.push('#');
             } else {
-                print!(".");
+                out.push('.');
             }
         }
-        println!();
+        out.push('\n');
     }
+    out
 }
 
 fn empty_surface(elflocs: &HashSet<Loc>) -> usize {
@@ -90,25 +75,24 @@ fn empty_surface(elflocs: &HashSet<Loc>) -> usize {
     empties
 }
 
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    let input = io::read_to_string(io::stdin())?;
-    let mut elflocs: HashSet<Loc> = Default::default();
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c == '#' {
-                elflocs.insert(Loc(x as isize, y as isize));
-            }
-        }
-    }
-    print_grid(&elflocs);
-    let mut proposals: HashMap<Loc, Vec<Loc>> = Default::default();
-    let mut propdirs = [Dir::N, Dir::S, Dir::W, Dir::E];
-    for step in 1.. {
-        for elfloc in &elflocs {
-            if elfloc.adjacents().iter().any(|l| elflocs.contains(l)) {
-                for dir in propdirs.iter() {
-                    if elfloc.propspace(*dir).iter().all(|l| !elflocs.contains(l)) {
+struct Grove {
+    elflocs: HashSet<Loc>,
+    /// the order the elves try directions in this round
+    propdirs: [Dir4; 4],
+    /// nobody moved last round
+    settled: bool,
+}
+
+impl Simulation for Grove {
+    /// (from, to) for every elf that moved this round
+    type Event = Vec<(Loc, Loc)>;
+
+    fn step(&mut self) -> Option<Self::Event> {
+        let mut proposals: HashMap<Loc, Vec<Loc>> = Default::default();
+        for elfloc in &self.elflocs {
+            if elfloc.adjacents().iter().any(|l| self.elflocs.contains(l)) {
+                for dir in self.propdirs.iter() {
+                    if elfloc.propspace(*dir).iter().all(|l| !self.elflocs.contains(l)) {
                         let dest = elfloc.go(*dir);
                         proposals.entry(dest).or_default().push(*elfloc);
                         break;
@@ -117,22 +101,137 @@ fn main() -> Result<()> {
             }
         }
         if proposals.is_empty() {
-            print_grid(&elflocs);
-            println!("No moves at step {step}");
-            break;
+            self.settled = true;
+            return None;
         }
+        let mut moves = vec![];
         for (dest, srcs) in proposals.drain() {
             if srcs.len() == 1 {
                 let src = srcs.first().unwrap();
-                elflocs.remove(src);
-                elflocs.insert(dest);
+                self.elflocs.remove(src);
+                self.elflocs.insert(dest);
+                moves.push((*src, dest));
             }
         }
-        propdirs.rotate_left(1);
-        if step == 10 {
-            print_grid(&elflocs);
-            println!("empties after step 10: {}", empty_surface(&elflocs));
+        self.propdirs.rotate_left(1);
+        Some(moves)
+    }
+
+    fn finished(&self) -> bool {
+        self.settled
+    }
+}
+
+impl Reversible for Grove {
+    type Cell = Loc;
+
+    fn undo(&mut self, moves: &Vec<(Loc, Loc)>) {
+        // nobody moves onto a place another elf left in the same round, so the order is free
+        for (from, to) in moves {
+            self.elflocs.remove(to);
+            self.elflocs.insert(*from);
         }
+        self.propdirs.rotate_right(1);
+        self.settled = false;
+    }
+
+    fn redo(&mut self, moves: &Vec<(Loc, Loc)>) {
+        for (from, to) in moves {
+            self.elflocs.remove(from);
+            self.elflocs.insert(*to);
+        }
+        self.propdirs.rotate_left(1);
+    }
+
+    fn cells(&self) -> BTreeSet<Loc> {
+        self.elflocs.iter().copied().collect()
+    }
+}
+
+/// What `--record` logs: where the elves start, then the moves made in each round.
+#[derive(Serialize, Deserialize)]
+enum Event {
+    Start(Vec<Loc>),
+    /// (from, to) for every elf that moved
+    Round(Vec<(Loc, Loc)>),
+}
+
+/// Rebuild the elves' positions from a `--record` log, as of the requested round.
+fn replay_log(replay: &Replay) -> Result<()> {
+    let mut elflocs = HashSet::new();
+    for Record { event, .. } in replay.events()? {
+        match event {
+            Event::Start(elves) => elflocs.extend(elves),
+            Event::Round(moves) => {
+                for (from, to) in moves {
+                    elflocs.remove(&from);
+                    elflocs.insert(to);
+                }
+            }
+        }
+    }
+    replay.show(render_grid(&elflocs))
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    problems.grid(check::lines(input), |c| c == '.' || c == '#');
+    problems
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    if let Some(replay) = Replay::requested()? {
+        return replay_log(&replay);
+    }
+    let puzzle = Puzzle::load()?;
+    let input = io::read_to_string(io::stdin())?;
+    let mut elflocs: HashSet<Loc> = Default::default();
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            if c == '#' {
+                elflocs.insert(Loc(x as isize, y as isize));
+            }
+        }
+    }
+    render::show("initial", render_grid(&elflocs))?;
+    let mut recorder = Recorder::open()?;
+    let mut elves: Vec<Loc> = elflocs.iter().copied().collect();
+    elves.sort();
+    recorder.record(0, Event::Start(elves))?;
+    let mut grove = Grove {
+        elflocs,
+        propdirs: [Dir4::Up, Dir4::Down, Dir4::Left, Dir4::Right],
+        settled: false,
+    };
+    let rewind = Rewind::requested()?;
+    let mut history = History::new();
+    let step = Runner::<Grove>::new()
+        .on_event(|_, step, moves| {
+            if rewind.is_some() {
+                history.record(moves.clone());
+            }
+            if recorder.enabled() {
+                let mut moves = moves.clone();
+                moves.sort();
+                recorder.record(step, Event::Round(moves))?;
+            }
+            Ok(())
+        })
+        .at(10, |grove| {
+            render::show("round-10", render_grid(&grove.elflocs))?;
+            println!("empties after step 10: {}", empty_surface(&grove.elflocs));
+            puzzle.answer(1, empty_surface(&grove.elflocs))
+        })
+        .run(&mut grove)?;
+    render::show("final", render_grid(&grove.elflocs))?;
+    println!("No moves at step {step}");
+    puzzle.answer(2, step)?;
+    if let Some(rewind) = rewind {
+        rewind.prompt(&mut grove, &mut history, |grove| render_grid(&grove.elflocs))?;
     }
 
     Ok(())
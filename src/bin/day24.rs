@@ -1,72 +1,58 @@
-use color_eyre::Result;
+use aoc2022::{
+    check::{self, Problems},
+    cycle,
+    dir::Dir4,
+    events::{Record, Recorder, Replay},
+    search::Search,
+    simulation::{Runner, Simulation},
+    Puzzle,
+};
+use color_eyre::{
+    eyre::{bail, ensure},
+    Result,
+};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashSet, VecDeque},
     hash::Hash,
     io,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Dir {
-    fn flip(self) -> Dir {
-        match self {
-            Dir::Up => Dir::Down,
-            Dir::Right => Dir::Left,
-            Dir::Down => Dir::Up,
-            Dir::Left => Dir::Right,
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Empty,
     Wall,
-    Blizzard(Dir),
+    Blizzard(Dir4),
 }
 
 impl Tile {
     fn from_char(c: char) -> Option<Tile> {
-        use Dir::*;
         use Tile::*;
         Some(match c {
             '.' => Empty,
             '#' => Wall,
-            '^' => Blizzard(Up),
-            'v' => Blizzard(Down),
-            '>' => Blizzard(Right),
-            '<' => Blizzard(Left),
+            '^' | 'v' | '>' | '<' => Blizzard(Dir4::from_char(c)?),
             _ => None?,
         })
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 struct Pos(u16, u16);
 
 impl Pos {
-    fn go(self, dir: Dir) -> Option<Pos> {
-        Some(match dir {
-            Dir::Up => Pos(self.0, self.1.checked_sub(1)?),
-            Dir::Right => Pos(self.0 + 1, self.1),
-            Dir::Down => Pos(self.0, self.1 + 1),
-            Dir::Left => Pos(self.0.checked_sub(1)?, self.1),
-        })
+    fn go(self, dir: Dir4) -> Option<Pos> {
+        let (x, y) = dir.go((self.0, self.1))?;
+        Some(Pos(x, y))
     }
 }
 
-fn advance_blizzards(blizzards: &mut [(Pos, Dir)], walls: &HashSet<Pos>) {
+fn advance_blizzards(blizzards: &mut [(Pos, Dir4)], walls: &HashSet<Pos>) {
     for (bpos, dir) in blizzards {
         let next = bpos.go(*dir).unwrap();
         let next = if walls.contains(&next) {
             let mut next = *bpos;
-            let flip = dir.flip();
+            let flip = dir.reverse();
             loop {
                 let back = next.go(flip).unwrap();
                 if walls.contains(&back) {
@@ -83,7 +69,23 @@ fn advance_blizzards(blizzards: &mut [(Pos, Dir)], walls: &HashSet<Pos>) {
     }
 }
 
-fn bloccupancy(blizzards: &[(Pos, Dir)]) -> HashSet<Pos> {
+/// The blizzards, which go their own way whatever the expedition does.
+struct Valley<'a> {
+    walls: &'a HashSet<Pos>,
+    blizzards: Vec<(Pos, Dir4)>,
+}
+
+impl Simulation for Valley<'_> {
+    /// where the blizzards moved to
+    type Event = Vec<(Pos, Dir4)>;
+
+    fn step(&mut self) -> Option<Vec<(Pos, Dir4)>> {
+        advance_blizzards(&mut self.blizzards, self.walls);
+        Some(self.blizzards.clone())
+    }
+}
+
+fn bloccupancy(blizzards: &[(Pos, Dir4)]) -> HashSet<Pos> {
     blizzards.iter().map(|(l, _)| *l).collect()
 }
 
@@ -101,11 +103,11 @@ impl State {
         bloccupancies: &[HashSet<Pos>],
         walls: &HashSet<Pos>,
     ) -> impl Iterator<Item = State> {
-        use Dir::*;
+        use Dir4::*;
         let blen = bloccupancies.len();
         let nidx = (self.time_elapsed as usize + 1) % blen;
         let nextblocced = &bloccupancies[nidx];
-        let can_go = |dir: Dir| {
+        let can_go = |dir: Dir4| {
             let next = match self.pos.go(dir) {
                 Some(pos) => pos,
                 None => return false,
@@ -124,7 +126,7 @@ impl State {
         }
         c.into_iter().flatten()
     }
-    fn advance(&self, dir: Option<Dir>) -> State {
+    fn advance(&self, dir: Option<Dir4>) -> State {
         State {
             time_elapsed: self.time_elapsed + 1,
             pos: match dir {
@@ -136,8 +138,84 @@ impl State {
     }
 }
 
+/// What `--record` logs: the walls as step 0, then where the blizzards are each minute until
+/// they repeat, minute M as step M + 1.
+#[derive(Serialize, Deserialize)]
+enum Event {
+    Start(Vec<Pos>),
+    Minute(Vec<(Pos, Dir4)>),
+}
+
+fn render_valley(walls: &[Pos], blizzards: &[(Pos, Dir4)]) -> String {
+    let width = walls.iter().map(|p| p.0).max().unwrap_or(0) + 1;
+    let height = walls.iter().map(|p| p.1).max().unwrap_or(0) + 1;
+    let mut rows = vec![vec!['.'; width as usize]; height as usize];
+    for wall in walls {
+        rows[wall.1 as usize][wall.0 as usize] = '#';
+    }
+    for (pos, dir) in blizzards {
+        let tile = &mut rows[pos.1 as usize][pos.0 as usize];
+        *tile = match (*tile, dir) {
+            ('.', dir) => dir.arrow(),
+            ('2'..='8', _) => (*tile as u8 + 1) as char,
+            _ => '2',
+        };
+    }
+    rows.into_iter()
+        .map(|row| row.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Rebuild the valley from a `--record` log, as of the requested step. The blizzards repeat, so
+/// minutes past the end of the log wrap around, and step 0 is the walls alone.
+fn replay_log(replay: &Replay) -> Result<()> {
+    let mut walls = vec![];
+    let mut minutes = vec![];
+    for Record { event, .. } in replay.all_events()? {
+        match event {
+            Event::Start(w) => walls = w,
+            Event::Minute(blizzards) => minutes.push(blizzards),
+        }
+    }
+    if minutes.is_empty() {
+        bail!("no minutes in the log");
+    }
+    let blizzards: &[(Pos, Dir4)] = match replay.step() {
+        Some(0) => &[],
+        Some(step) => &minutes[(step as usize - 1) % minutes.len()],
+        None => &minutes[minutes.len() - 1],
+    };
+    replay.show(render_valley(&walls, blizzards))
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let lines: Vec<(usize, &str)> = check::lines(input).collect();
+    problems.grid(lines.iter().copied(), |c| Tile::from_char(c).is_some());
+    for (which, row) in [("start", lines.first()), ("end", lines.last())] {
+        match row {
+            Some((lnum, line)) if line.matches('.').count() != 1 => {
+                problems.at(
+                    *lnum,
+                    format!("expected one gap in the wall for the {which}"),
+                );
+            }
+            None => problems.input("empty input"),
+            _ => {}
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    if let Some(replay) = Replay::requested()? {
+        return replay_log(&replay);
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let mut walls = HashSet::new();
     let mut blizzards = vec![];
@@ -165,17 +243,30 @@ fn main() -> Result<()> {
     }
     let start = start.unwrap();
     let end = end.unwrap();
-    let mut bloccs = vec![];
-    {
-        let mut uniqbloccs = HashSet::new();
-        loop {
-            if !uniqbloccs.insert(blizzards.clone()) {
-                break;
-            }
-            bloccs.push(bloccupancy(&blizzards[..]));
-            advance_blizzards(&mut blizzards, &walls);
-        }
-    }
+    let mut recorder = Recorder::open()?;
+    let mut sorted_walls: Vec<Pos> = walls.iter().copied().collect();
+    sorted_walls.sort();
+    recorder.record(0, Event::Start(sorted_walls))?;
+    let (prefix, period) = cycle::brent(&blizzards, |blizzards| {
+        let mut blizzards = blizzards.clone();
+        advance_blizzards(&mut blizzards, &walls);
+        blizzards
+    });
+    // next_states wraps time around the period, which only works if minute 0 is part of it
+    ensure!(prefix == 0, "blizzards only start repeating after minute {prefix}");
+    recorder.record(1, Event::Minute(blizzards.clone()))?;
+    let mut bloccs = vec![bloccupancy(&blizzards[..])];
+    let mut valley = Valley {
+        walls: &walls,
+        blizzards,
+    };
+    Runner::new()
+        .on_event(|_: &Valley, minute, blizzards| {
+            recorder.record(minute + 1, Event::Minute(blizzards.clone()))?;
+            bloccs.push(bloccupancy(blizzards));
+            Ok(())
+        })
+        .run_n(&mut valley, period as u64 - 1)?;
     println!("{start:?} -> {end:?}, blocc cycle: {}", bloccs.len());
     let mut q = VecDeque::new();
     q.push_back(State {
@@ -186,7 +277,11 @@ fn main() -> Result<()> {
     });
     let mut found = None;
     let mut visited = HashSet::new();
+    let mut search = Search::new("path")?;
     while let Some(mut state) = q.pop_front() {
+        if !search.expand(q.len()) {
+            break;
+        }
         if state.pos == end && !state.need_snack {
             state.need_snack = true;
         }
@@ -194,6 +289,7 @@ fn main() -> Result<()> {
             state.has_snack = true;
         }
         if visited.contains(&state) {
+            search.prune();
             continue;
         }
         visited.insert(state);
@@ -202,16 +298,17 @@ fn main() -> Result<()> {
             None => {
                 if goal_reached {
                     found = Some(state);
-                    println!("found path: {:?}", found);
+                    search.improved(state.time_elapsed);
                 }
             }
             Some(best) => {
                 if state.time_elapsed > best.time_elapsed {
+                    search.prune();
                     continue;
                 }
                 if goal_reached && best.time_elapsed > state.time_elapsed {
-                    println!("better path: {:?}", found);
                     found = Some(state);
+                    search.improved(state.time_elapsed);
                 }
             }
         }
@@ -220,5 +317,12 @@ fn main() -> Result<()> {
         }
     }
     println!("best path: {:?}", found);
+    let complete = search.finish();
+    let found = match found {
+        Some(found) => found,
+        None if !complete => bail!("no path found within --time-budget"),
+        None => bail!("no path"),
+    };
+    puzzle.answer_searched(2, found.time_elapsed, complete)?;
     Ok(())
 }
\ No newline at end of file
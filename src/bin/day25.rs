@@ -1,4 +1,8 @@
-use color_eyre::Result;
+use aoc2022::{
+    check::{self, Problems},
+    checked, Puzzle,
+};
+use color_eyre::{eyre::Context, Result};
 use std::io;
 
 fn sn2digit(c: char) -> i64 {
@@ -30,18 +34,38 @@ fn tosn(n: i64) -> String {
     out.into_iter().rev().collect()
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        if line.is_empty() {
+            problems.at(lnum, "empty number");
+        }
+        problems.chars(lnum, line, |c| "=-012".contains(c));
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let input = io::read_to_string(io::stdin())?;
     let mut total = 0;
     for line in input.lines() {
         let val: i64 = std::iter::zip(line.chars().map(sn2digit), (0..line.len()).rev())
-            .map(|(v, place)| v * (5_i64.pow(place as u32)))
-            .sum();
+            .try_fold(0, |acc, (v, place)| {
+                let place = checked::pow("SNAFU place", 5_i64, place as u32)?;
+                let digit = checked::mul("SNAFU digit", v, place)?;
+                checked::add("SNAFU number", acc, digit)
+            })
+            .wrap_err_with(|| format!("reading {line}"))?;
         println!("{line} = {val}");
-        total += val;
+        total = checked::add("SNAFU total", total, val)?;
     }
     println!("total: {total}");
     println!("ans: {}", tosn(total));
+    puzzle.answer(1, tosn(total))?;
     Ok(())
 }
@@ -1,5 +1,13 @@
-use color_eyre::{eyre::{bail, ContextCompat}, Result};
-use std::{collections::HashSet, hash::Hash};
+use aoc2022::{
+    args::arg_value,
+    check::{self, Problems},
+    Puzzle,
+};
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use std::ops::BitAnd;
 
 #[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
 struct Item(char);
@@ -23,13 +31,89 @@ impl Item {
             27 + self.0 as u32 - 'A' as u32
         }
     }
+
+    fn from_priority(priority: u32) -> Item {
+        if priority <= 26 {
+            Item((b'a' + priority as u8 - 1) as char)
+        } else {
+            Item((b'A' + priority as u8 - 27) as char)
+        }
+    }
+}
+
+/// A set of items as a 52-bit mask, bit `priority - 1` for each.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    /// Everything, so intersecting a group's rucksacks can start from it.
+    const ALL: ItemSet = ItemSet((1 << 52) - 1);
+
+    fn of(items: &[Item]) -> ItemSet {
+        ItemSet(items.iter().fold(0, |mask, item| mask | 1 << (item.priority() - 1)))
+    }
+
+    /// The lowest-priority item in the set.
+    fn first(self) -> Option<Item> {
+        (self.0 != 0).then(|| Item::from_priority(self.0.trailing_zeros() + 1))
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+}
+
+fn check_input(input: &str, group_size: usize) -> Problems {
+    let mut problems = Problems::new();
+    let rucksacks: Vec<(usize, &str)> = check::lines(input).collect();
+    for &(lnum, line) in &rucksacks {
+        problems.chars(lnum, line, |c| c.is_ascii_alphabetic());
+        if line.len() % 2 != 0 {
+            problems.at(lnum, format!("odd number of items ({})", line.len()));
+        } else if line.is_ascii() {
+            let (part_a, part_b) = line.split_at(line.len() / 2);
+            if !part_a.chars().any(|c| part_b.contains(c)) {
+                problems.at(lnum, "no item is in both compartments");
+            }
+        }
+    }
+    for group in rucksacks.chunks(group_size) {
+        if group.len() < group_size {
+            problems.input(format!(
+                "last group only has {} of {group_size} rucksacks",
+                group.len()
+            ));
+        } else if !group[0]
+            .1
+            .chars()
+            .any(|c| group[1..].iter().all(|(_, line)| line.contains(c)))
+        {
+            problems.at(group[0].0, "group has no badge item in common");
+        }
+    }
+    problems
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let mut puzzle = Puzzle::load()?;
+    if let Some(group_size) = arg_value::<usize>("--group-size")? {
+        puzzle.set_param("group_size", group_size);
+    }
+    let group_size: usize = puzzle.param_or("group_size", 3)?;
+    if group_size == 0 {
+        bail!("the group size must be at least 1");
+    }
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?, group_size).report();
+    }
     let mut missort_total_prio = 0;
     let mut total_badge_prio = 0;
-    let mut current_group = Vec::new();
+    let mut current_group = ItemSet::ALL;
+    let mut group_len = 0;
     for line in std::io::stdin().lines() {
         let line = line?;
         let items: Vec<Item> = line.chars().map(Item::try_from).collect::<Result<_>>()?;
@@ -37,27 +121,29 @@ fn main() -> Result<()> {
         if part_a.len() != part_b.len() {
             bail!("uneven rucksack!");
         }
-        let paset: HashSet<Item> = part_a.iter().cloned().collect();
-        let pbset: HashSet<Item> = part_b.iter().cloned().collect();
-        let missort = paset.intersection(&pbset).next().context("no missort?")?;
+        let missort = (ItemSet::of(part_a) & ItemSet::of(part_b))
+            .first()
+            .context("no missort?")?;
         let msprio = missort.priority();
         missort_total_prio += msprio;
         eprintln!("missort: {:?}, {}", missort, msprio);
-        current_group.push(items);
-        if current_group.len() == 3 {
-            let sets: Vec<HashSet<_>> = current_group
-                .iter()
-                .map(|g| g.iter().cloned().collect::<HashSet<_>>())
-                .collect();
-            let g1set: HashSet<_> = sets[0].intersection(&sets[1]).cloned().collect();
-            let badge = g1set.intersection(&sets[2]).next().context("no badge?")?;
+        current_group = current_group & ItemSet::of(&items);
+        group_len += 1;
+        if group_len == group_size {
+            let badge = current_group.first().context("no badge?")?;
             let badge_prio = badge.priority();
-            current_group.clear();
+            current_group = ItemSet::ALL;
+            group_len = 0;
             total_badge_prio += badge_prio;
             eprintln!("badge: {:?} ({})", badge, badge_prio);
         }
     }
     println!("Total missort priority: {}", missort_total_prio);
+    puzzle.answer(1, missort_total_prio)?;
+    if group_len != 0 {
+        bail!("last group only has {group_len} of {group_size} rucksacks");
+    }
     println!("Total badge priority: {}", total_badge_prio);
+    puzzle.answer(2, total_badge_prio)?;
     Ok(())
 }
@@ -1,27 +1,38 @@
+use aoc2022::{
+    check::{self, Problems},
+    interval::IntervalSet,
+    Puzzle,
+};
 use color_eyre::{
     eyre::{ContextCompat},
     Result,
 };
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Interval {
-    start: u32,
-    end: u32,
-}
-
-impl Interval {
-    // self fully contains other
-    fn contains(&self, other: &Interval) -> bool {
-        self.start <= other.start && self.end >= other.end
-    }
-
-    // self overlaps other at all
-    fn overlaps(&self, other: &Interval) -> bool {
-        self.start.max(other.start) <= self.end.min(other.end)
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        match strp::try_scan!(line => "{}-{},{}-{}") {
+            Ok((a, b, c, d)) => {
+                let (a, b, c, d): (u32, u32, u32, u32) = (a, b, c, d);
+                if a > b {
+                    problems.at(lnum, format!("first range {a}-{b} is backwards"));
+                }
+                if c > d {
+                    problems.at(lnum, format!("second range {c}-{d} is backwards"));
+                }
+            }
+            Err(_) => problems.at(lnum, format!("expected a-b,c-d, got {line:?}")),
+        }
     }
+    problems
 }
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let mut total_full_overlaps = 0;
     let mut total_partial_overlaps = 0;
     for line in std::io::stdin().lines() {
@@ -29,12 +40,14 @@ fn main() -> Result<()> {
         let (a, b, c, d) = strp::try_scan!(line => "{}-{},{}-{}")
             .ok()
             .context("parse interval pair")?;
-        let ia = Interval { start: a, end: b };
-        let ib = Interval { start: c, end: d };
-        if ia.contains(&ib) || ib.contains(&ia) { total_full_overlaps += 1; }
-        if ia.overlaps(&ib) { total_partial_overlaps += 1; }
+        let ia: IntervalSet<u32> = (a..=b).into();
+        let ib: IntervalSet<u32> = (c..=d).into();
+        if ia.is_superset(&ib) || ib.is_superset(&ia) { total_full_overlaps += 1; }
+        if !ia.is_disjoint(&ib) { total_partial_overlaps += 1; }
     }
     println!("Total full overlaps: {}", total_full_overlaps);
     println!("Total partial overlaps: {}", total_partial_overlaps);
+    puzzle.answer(1, total_full_overlaps)?;
+    puzzle.answer(2, total_partial_overlaps)?;
     Ok(())
 }
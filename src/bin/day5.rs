@@ -1,3 +1,7 @@
+use aoc2022::{
+    check::{self, Problems},
+    render, Puzzle,
+};
 use color_eyre::{
     eyre::{ContextCompat},
     Result,
@@ -30,7 +34,7 @@ fn parse_crate_stacks(mut stacklines: Vec<String>) -> Result<Vec<Vec<char>>> {
     Ok(stacks)
 }
 
-fn print_stacks(mut stacks: Vec<Vec<char>>) {
+fn render_stacks(mut stacks: Vec<Vec<char>>) -> String {
     let mut lines = Vec::new();
     let nstacks = stacks.len();
     let botline = (0..nstacks)
@@ -59,11 +63,84 @@ fn print_stacks(mut stacks: Vec<Vec<char>>) {
         }
     }
     lines.reverse();
-    eprintln!("{}", lines.join("\n"));
+    lines.join("\n")
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let lines: Vec<(usize, &str)> = check::lines(input).collect();
+    let Some(split) = lines.iter().position(|(_, l)| l.is_empty()) else {
+        problems.input("no blank line between the crate drawing and the moves");
+        return problems;
+    };
+    let (drawing, moves) = (&lines[..split], &lines[split + 1..]);
+    let Some(&(numlnum, numline)) = drawing.last() else {
+        problems.input("no crate drawing");
+        return problems;
+    };
+    let numbers: Vec<&str> = numline.split_whitespace().collect();
+    for (idx, n) in numbers.iter().enumerate() {
+        if *n != (idx + 1).to_string() {
+            problems.at(
+                numlnum,
+                format!("expected stack number {}, got {n:?}", idx + 1),
+            );
+        }
+    }
+    let nstacks = numbers.len();
+    let mut heights = vec![0; nstacks];
+    for &(lnum, line) in drawing[..drawing.len() - 1].iter().rev() {
+        let chars: Vec<char> = line.chars().collect();
+        for (idx, slot) in chars.chunks(4).enumerate() {
+            match slot {
+                [' ', ' ', ' '] | [' ', ' ', ' ', ' '] => {}
+                ['[', c, ']'] | ['[', c, ']', ' '] if c.is_ascii_alphabetic() && idx < nstacks => {
+                    heights[idx] += 1;
+                }
+                _ => problems.at(
+                    lnum,
+                    format!("expected a crate or gap at column {}", idx * 4 + 1),
+                ),
+            }
+        }
+    }
+    for &(lnum, line) in moves {
+        let Ok((n, from, to)) = strp::try_scan!(line => "move {} from {} to {}") else {
+            problems.at(lnum, format!("expected 'move N from A to B', got {line:?}"));
+            continue;
+        };
+        let (n, from, to): (u32, u32, u32) = (n, from, to);
+        let (n, from, to) = (n as usize, from as usize, to as usize);
+        let mut in_range = true;
+        for stack in [from, to] {
+            if stack == 0 || stack > nstacks {
+                problems.at(lnum, format!("there's no stack {stack}"));
+                in_range = false;
+            }
+        }
+        if in_range {
+            if heights[from - 1] < n {
+                problems.at(
+                    lnum,
+                    format!(
+                        "moves {n} crates from stack {from}, which only has {}",
+                        heights[from - 1]
+                    ),
+                );
+            }
+            heights[from - 1] = heights[from - 1].saturating_sub(n);
+            heights[to - 1] += n;
+        }
+    }
+    problems
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     // use arg --part2 for part2
     let do_part2 = std::env::args().any(|i| i == "--part2");
     let mut stacklines = Vec::new();
@@ -108,11 +185,12 @@ fn main() -> Result<()> {
         }
     }
     eprintln!("End state:");
-    print_stacks(stacks.clone());
+    render::eshow("stacks", render_stacks(stacks.clone()))?;
     let msg = stacks
         .iter()
         .map(|stack| stack.last().unwrap_or(&' '))
         .collect::<String>();
     println!("Final message: {}", msg);
+    puzzle.answer(if do_part2 { 2 } else { 1 }, msg)?;
     Ok(())
 }
@@ -1,10 +1,14 @@
+use aoc2022::{
+    check::{self, Problems},
+    impls, Puzzle,
+};
 use color_eyre::Result;
 
 fn sop_index(line: &str, n_distinct: usize) -> Option<usize> {
     if line.len() < n_distinct {
         return None;
     }
-    for i in n_distinct..line.len() {
+    for i in n_distinct..=line.len() {
         let marker = &line[i - n_distinct..i];
         // assuming these are all lowercase ascii so we can just use a u32 and popcnt
         let mut mask: u32 = 0;
@@ -20,14 +24,56 @@ fn sop_index(line: &str, n_distinct: usize) -> Option<usize> {
     None
 }
 
+/// The same, comparing every pair of characters in the window.
+fn sop_index_naive(line: &str, n_distinct: usize) -> Option<usize> {
+    let bytes = line.as_bytes();
+    (n_distinct..=bytes.len()).find(|&i| {
+        let marker = &bytes[i - n_distinct..i];
+        (0..marker.len()).all(|j| !marker[j + 1..].contains(&marker[j]))
+    })
+}
+
+type SopIndex = fn(&str, usize) -> Option<usize>;
+const IMPLS: [(&str, SopIndex); 2] = [("popcount", sop_index), ("naive", sop_index_naive)];
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    if input.trim().is_empty() {
+        problems.input("empty input");
+    }
+    for (lnum, line) in check::lines(input) {
+        problems.chars(lnum, line, |c| c.is_ascii_lowercase());
+        if line.len() < 14 {
+            problems.at(
+                lnum,
+                format!("only {} characters, a message marker needs 14", line.len()),
+            );
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let sop_index = impls::chosen(&IMPLS)?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     for line in std::io::stdin().lines() {
         let line = line?;
         // part1
-        println!("Start of packet: {:?}", sop_index(&line, 4));
+        let sop = sop_index(&line, 4);
+        println!("Start of packet: {:?}", sop);
+        if let Some(sop) = sop {
+            puzzle.answer(1, sop)?;
+        }
         // part2
-        println!("Start of msg: {:?}", sop_index(&line, 14));
+        let som = sop_index(&line, 14);
+        println!("Start of msg: {:?}", som);
+        if let Some(som) = som {
+            puzzle.answer(2, som)?;
+        }
     }
     Ok(())
 }
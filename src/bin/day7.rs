@@ -1,6 +1,13 @@
 use std::collections::{BTreeMap, HashMap};
 
-use color_eyre::{eyre::ContextCompat, Result};
+use aoc2022::{
+    check::{self, Problems},
+    Puzzle,
+};
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
 
 #[derive(Debug, Eq, PartialEq)]
 enum DirectoryEntry {
@@ -8,8 +15,40 @@ enum DirectoryEntry {
     File(u64),
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    let mut reading_ls = false;
+    for (lnum, line) in check::lines(input) {
+        if let Some(cmd) = line.strip_prefix("$ ") {
+            reading_ls = false;
+            match cmd.split_whitespace().collect::<Vec<_>>()[..] {
+                ["cd", _] => {}
+                ["ls"] => reading_ls = true,
+                _ => problems.at(lnum, format!("unknown command {cmd:?}")),
+            }
+        } else if !reading_ls {
+            problems.at(lnum, "output outside of an ls listing");
+        } else if line.strip_prefix("dir ").is_none() {
+            match line.split_once(' ') {
+                Some((size, _)) => {
+                    problems.parse::<u64>(lnum, size, "a file size");
+                }
+                None => problems.at(
+                    lnum,
+                    format!("expected 'dir NAME' or 'SIZE NAME', got {line:?}"),
+                ),
+            }
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     // Ord for Vec compares each element in order, so representing paths as a Vec<String>
     // in an ordered map both makes for an easy way both to deal with cwd, and makes each dir's
     // recursive subelements lie in a contiguous range we can easily iterate over.
@@ -79,14 +118,20 @@ fn main() -> Result<()> {
     // part1
     let totalover100k: u64 = dirsizes.values().filter(|s| **s <= 100000).sum();
     println!("Part1, sum of dirs <=100k: {}", totalover100k);
+    puzzle.answer(1, totalover100k)?;
     // part2
-    const TOTAL_SPACE: u64 = 70000000;
-    const NEEDED_SPACE: u64 = 30000000;
+    let total_space: u64 = puzzle.param_or("total_space", 70000000)?;
+    let needed_space: u64 = puzzle.param_or("needed_space", 30000000)?;
     let used_space = *dirsizes.get(&vec![]).context("size of /")?;
-    let free_space = TOTAL_SPACE - used_space;
-    let needed_additional_space = NEEDED_SPACE - free_space;
+    let Some(free_space) = total_space.checked_sub(used_space) else {
+        bail!("{used_space} used is more than the total_space of {total_space}");
+    };
+    let Some(needed_additional_space) = needed_space.checked_sub(free_space) else {
+        bail!("{free_space} is free already, more than the needed_space of {needed_space}");
+    };
     let big_enough = dirsizes.values().filter(|s| **s >= needed_additional_space);
     let min_bigenough = *big_enough.min().context("big enough?")?;
     println!("Part2, delete a dir of size: {}", min_bigenough);
+    puzzle.answer(2, min_bigenough)?;
     Ok(())
 }
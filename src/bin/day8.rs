@@ -1,3 +1,7 @@
+use aoc2022::{
+    check::{self, Problems},
+    Puzzle,
+};
 use color_eyre::{eyre::ContextCompat, Result};
 
 struct TreeGrid {
@@ -109,8 +113,18 @@ enum Visibility {
     Visible,
 }
 
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    problems.grid(check::lines(input), |c| c.is_ascii_digit());
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    let puzzle = Puzzle::load()?;
     let mut heights: Vec<u8> = Vec::new();
     let mut grid_stride = None;
     for line in std::io::stdin().lines() {
@@ -134,6 +148,7 @@ fn main() -> Result<()> {
         .filter(|x| **x == Visibility::Visible)
         .count();
     println!("Trees visible from edges: {}", total_edge_visible);
+    puzzle.answer(1, total_edge_visible)?;
     let mut best_loc = None;
     let mut best_score = None;
     for x in 0..treegrid.width() {
@@ -150,5 +165,6 @@ fn main() -> Result<()> {
         best_score.unwrap(),
         best_loc.unwrap()
     );
+    puzzle.answer(2, best_score.unwrap())?;
     Ok(())
 }
@@ -1,19 +1,15 @@
 use std::collections::HashSet;
 
-use color_eyre::{
-    eyre::{bail, ContextCompat},
-    Result,
+use aoc2022::{
+    check::{self, Problems},
+    dir::Dir4,
+    events::{Record, Recorder, Replay},
+    Puzzle,
 };
+use color_eyre::{eyre::ContextCompat, Result};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Motion {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 struct Loc {
     x: i32,
     y: i32,
@@ -23,25 +19,11 @@ impl Loc {
     fn new(x: i32, y: i32) -> Loc {
         Loc { x, y }
     }
-    fn moved(self, dir: Motion, dist: i32) -> Loc {
-        use Motion::*;
-        match dir {
-            Up => Loc {
-                x: self.x,
-                y: self.y - dist,
-            },
-            Down => Loc {
-                x: self.x,
-                y: self.y + dist,
-            },
-            Left => Loc {
-                x: self.x - dist,
-                y: self.y,
-            },
-            Right => Loc {
-                x: self.x + dist,
-                y: self.y,
-            },
+    fn moved(self, dir: Dir4, dist: i32) -> Loc {
+        let (dx, dy) = dir.offset();
+        Loc {
+            x: self.x + dx as i32 * dist,
+            y: self.y + dy as i32 * dist,
         }
     }
     fn within(self, other: Loc, dist: i32) -> bool {
@@ -49,30 +31,87 @@ impl Loc {
     }
 }
 
+/// What `--record` logs: every knot's position after each step the head takes.
+#[derive(Serialize, Deserialize)]
+struct Knots(Vec<Loc>);
+
+/// The rope (H, then 1.. or T) over the places the tail has been (#).
+fn render_rope(rope: &[Loc], visited: &HashSet<Loc>) -> String {
+    let all = || rope.iter().chain(visited);
+    let (minx, maxx) = (all().map(|l| l.x).min(), all().map(|l| l.x).max());
+    let (miny, maxy) = (all().map(|l| l.y).min(), all().map(|l| l.y).max());
+    let (Some(minx), Some(maxx), Some(miny), Some(maxy)) = (minx, maxx, miny, maxy) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    for y in miny..=maxy {
+        for x in minx..=maxx {
+            let here = Loc::new(x, y);
+            out.push(match rope.iter().position(|&k| k == here) {
+                Some(0) => 'H',
+                Some(1) if rope.len() == 2 => 'T',
+                Some(idx) => char::from_digit(idx as u32, 36).unwrap_or('?'),
+                None if visited.contains(&here) => '#',
+                None => '.',
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Rebuild the rope and the tail's trail from a `--record` log, as of the requested step.
+fn replay_log(replay: &Replay) -> Result<()> {
+    let mut rope = vec![];
+    let mut visited = HashSet::new();
+    for Record { event, .. } in replay.events::<Knots>()? {
+        rope = event.0;
+        visited.extend(rope.last().copied());
+    }
+    replay.show(render_rope(&rope, &visited))
+}
+
+fn check_input(input: &str) -> Problems {
+    let mut problems = Problems::new();
+    for (lnum, line) in check::lines(input) {
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [dir, dist] => {
+                if !["U", "D", "L", "R"].contains(&dir) {
+                    problems.at(lnum, format!("unknown direction {dir:?}"));
+                }
+                problems.parse::<i32>(lnum, dist, "a distance");
+            }
+            _ => problems.at(lnum, format!("expected 'DIR N', got {line:?}")),
+        }
+    }
+    problems
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
-    let num_knots = if std::env::args().any(|x| x.contains("part2")) {
-        10
-    } else {
-        2
-    };
+    if check::requested() {
+        return check_input(&std::io::read_to_string(std::io::stdin())?).report();
+    }
+    if let Some(replay) = Replay::requested()? {
+        return replay_log(&replay);
+    }
+    let puzzle = Puzzle::load()?;
+    let do_part2 = std::env::args().any(|x| x.contains("part2"));
+    let num_knots = if do_part2 { 10 } else { 2 };
     let mut moves = Vec::new();
     for line in std::io::stdin().lines() {
         let line = line?;
         let mut splits = line.split_whitespace();
-        let dir = match splits.next().context("move direction")? {
-            "U" => Motion::Up,
-            "D" => Motion::Down,
-            "L" => Motion::Left,
-            "R" => Motion::Right,
-            _ => bail!("bad move direction"),
-        };
+        let dir: Dir4 = splits.next().context("move direction")?.parse()?;
         let distance: i32 = splits.next().context("move length")?.parse()?;
         moves.push((dir, distance));
     }
     let mut rope = vec![Loc::new(0, 0); num_knots];
     let mut tailpath = Vec::new();
     tailpath.push(*rope.last().unwrap());
+    let mut recorder = Recorder::open()?;
+    let mut step = 0;
+    recorder.record(step, Knots(rope.clone()))?;
     for (dir, dist) in moves {
         for _m in 0..dist {
             rope[0] = rope[0].moved(dir, 1);
@@ -88,10 +127,15 @@ fn main() -> Result<()> {
                     rope[segment] = newloc;
                 }
             }
+            step += 1;
+            if recorder.enabled() {
+                recorder.record(step, Knots(rope.clone()))?;
+            }
         }
     }
     let mut tail_uniqs = HashSet::new();
     tail_uniqs.extend(tailpath.iter().cloned());
     println!("Unique tail positions: {}", tail_uniqs.len());
+    puzzle.answer(if do_part2 { 2 } else { 1 }, tail_uniqs.len())?;
     Ok(())
 }
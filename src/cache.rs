@@ -0,0 +1,129 @@
+//! On-disk cache of day results, so `aoc run` doesn't redo a slow day whose input and code
+//! haven't changed.
+//!
+//! An entry is keyed by a hash of everything that can change the answers: the day, which part
+//! it was asked for, its arguments, the input bytes, the input's metadata file and the solver
+//! version. The solver version is a hash of the day binary itself, so any rebuild that changes
+//! the code invalidates its entries.
+//!
+//! Entries live in `AOC_CACHE_DIR`, or `target/aoc-cache` next to the binaries by default, so
+//! `cargo clean` clears them too.
+
+use color_eyre::{eyre::Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet},
+    env, fs,
+    hash::Hasher,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub answers: BTreeMap<u8, String>,
+    /// parts answered by a search cut short, which never make it into the cache
+    #[serde(default)]
+    pub unproven: BTreeSet<u8>,
+    /// how long the run that produced the answers took
+    pub elapsed: Duration,
+}
+
+/// Builds a cache key out of any number of byte strings.
+#[derive(Default)]
+pub struct Key(DefaultHasher);
+
+impl Key {
+    pub fn with(mut self, bytes: impl AsRef<[u8]>) -> Key {
+        let bytes = bytes.as_ref();
+        // length first, so ("ab", "c") and ("a", "bc") differ
+        self.0.write_usize(bytes.len());
+        self.0.write(bytes);
+        self
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0.finish())
+    }
+}
+
+/// Identifies the code behind a day binary: a hash of its contents.
+pub fn solver_version(exe: &Path) -> Result<String> {
+    let bytes = fs::read(exe).wrap_err_with(|| format!("reading {}", exe.display()))?;
+    Ok(Key::default().with(bytes).finish())
+}
+
+pub struct Cache {
+    dir: PathBuf,
+}
+
+/// `target/<name>`, found from where the running binary is.
+pub(crate) fn target_subdir(name: &str) -> Result<PathBuf> {
+    // binaries are in target/<profile>/
+    let exe = env::current_exe()?;
+    let profile_dir = exe.parent().unwrap_or(Path::new("."));
+    Ok(profile_dir.parent().unwrap_or(profile_dir).join(name))
+}
+
+impl Cache {
+    pub fn open() -> Result<Cache> {
+        let dir = match env::var_os(CACHE_DIR_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => target_subdir("aoc-cache")?,
+        };
+        Ok(Cache { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, day: &str, key: &str) -> PathBuf {
+        self.dir.join(format!("{day}-{key}.json"))
+    }
+
+    pub fn get(&self, day: &str, key: &str) -> Result<Option<Entry>> {
+        let path = self.path(day, key);
+        match fs::read_to_string(&path) {
+            Ok(text) => match serde_json::from_str(&text) {
+                Ok(entry) => Ok(Some(entry)),
+                // written by some other version of this code, just recompute it
+                Err(_) => Ok(None),
+            },
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).wrap_err_with(|| format!("reading {}", path.display())),
+        }
+    }
+
+    pub fn put(&self, day: &str, key: &str, entry: &Entry) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(day, key);
+        fs::write(&path, serde_json::to_string(entry)?)
+            .wrap_err_with(|| format!("writing {}", path.display()))
+    }
+
+    /// Remove every entry, or just those for `day`. Returns how many went.
+    pub fn clear(&self, day: Option<&str>) -> Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let Some((entry_day, _)) = name.split_once('-') else {
+                continue;
+            };
+            if name.ends_with(".json") && day.map(|d| d == entry_day).unwrap_or(true) {
+                fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
@@ -0,0 +1,118 @@
+//! `--check`: validate an input without solving it.
+//!
+//! The parsers bail on the first thing they don't like (or quietly skip it), which makes fixing
+//! up a hand-written input tedious. Each day has a `check_input` that instead notes every
+//! structural problem it can find, with line numbers, in a [`Problems`].
+
+use color_eyre::{eyre::bail, Result};
+use std::fmt::Display;
+
+pub fn requested() -> bool {
+    std::env::args().any(|a| a == "--check")
+}
+
+#[derive(Debug, Default)]
+pub struct Problems {
+    found: Vec<(Option<usize>, String)>,
+}
+
+impl Problems {
+    pub fn new() -> Problems {
+        Problems::default()
+    }
+
+    /// A problem on (1-based) line `line`.
+    pub fn at(&mut self, line: usize, msg: impl Display) {
+        self.found.push((Some(line), msg.to_string()));
+    }
+
+    /// A problem with the input as a whole.
+    pub fn input(&mut self, msg: impl Display) {
+        self.found.push((None, msg.to_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.found.is_empty()
+    }
+
+    /// Parse `s`, noting a problem if it isn't a `what`.
+    pub fn parse<T: std::str::FromStr>(&mut self, line: usize, s: &str, what: &str) -> Option<T> {
+        let parsed = s.parse().ok();
+        if parsed.is_none() {
+            self.at(line, format!("expected {what}, got {s:?}"));
+        }
+        parsed
+    }
+
+    /// Check a character grid is rectangular and only contains known tiles.
+    pub fn grid<'a>(
+        &mut self,
+        rows: impl IntoIterator<Item = (usize, &'a str)>,
+        known: impl Fn(char) -> bool,
+    ) {
+        let mut width = None;
+        let mut nrows = 0;
+        for (lnum, row) in rows {
+            nrows += 1;
+            let w = row.chars().count();
+            match width {
+                None => width = Some(w),
+                Some(width) if width != w => {
+                    self.at(lnum, format!("row is {w} wide, expected {width}"))
+                }
+                _ => {}
+            }
+            self.chars(lnum, row, &known);
+        }
+        if nrows == 0 {
+            self.input("empty grid");
+        }
+    }
+
+    /// Note every character of `line` that isn't `known`, by column.
+    pub fn chars(&mut self, lnum: usize, line: &str, known: impl Fn(char) -> bool) {
+        for (col, c) in line.chars().enumerate() {
+            if !known(c) {
+                self.at(lnum, format!("unexpected {c:?} at column {}", col + 1));
+            }
+        }
+    }
+
+    /// Print what was found, failing if there was anything.
+    pub fn report(self) -> Result<()> {
+        for (line, msg) in &self.found {
+            match line {
+                Some(line) => println!("line {line}: {msg}"),
+                None => println!("input: {msg}"),
+            }
+        }
+        if !self.found.is_empty() {
+            bail!("{} problem(s) in input", self.found.len());
+        }
+        println!("input ok");
+        Ok(())
+    }
+}
+
+/// Lines paired with their 1-based line numbers.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate().map(|(i, l)| (i + 1, l))
+}
+
+/// Blank-line-separated blocks of numbered lines.
+pub fn blocks(input: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut blocks = vec![vec![]];
+    for (lnum, line) in lines(input) {
+        if line.trim().is_empty() {
+            if !blocks.last().unwrap().is_empty() {
+                blocks.push(vec![]);
+            }
+        } else {
+            blocks.last_mut().unwrap().push((lnum, line));
+        }
+    }
+    if blocks.last().unwrap().is_empty() {
+        blocks.pop();
+    }
+    blocks
+}
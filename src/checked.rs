@@ -0,0 +1,68 @@
+//! `--checked`: overflow-checked arithmetic for the days that do big integer math.
+//!
+//! Normally these are the plain operators, so a release build silently wraps on overflow and
+//! integer division truncates. With `--checked` on the command line they fail instead, saying
+//! what overflowed (or didn't divide exactly), and the days add context about where.
+
+use color_eyre::{eyre::eyre, Result};
+use num::{
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
+    Integer, One,
+};
+use std::{any::type_name, fmt::Display, sync::OnceLock};
+
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| std::env::args().any(|a| a == "--checked"))
+}
+
+fn overflow<T>(what: &str, expr: impl Display) -> color_eyre::Report {
+    eyre!("{what}: {expr} overflows {}", type_name::<T>())
+}
+
+pub fn add<T: CheckedAdd + Display>(what: &str, a: T, b: T) -> Result<T> {
+    if !enabled() {
+        return Ok(a + b);
+    }
+    a.checked_add(&b)
+        .ok_or_else(|| overflow::<T>(what, format!("{a} + {b}")))
+}
+
+pub fn sub<T: CheckedSub + Display>(what: &str, a: T, b: T) -> Result<T> {
+    if !enabled() {
+        return Ok(a - b);
+    }
+    a.checked_sub(&b)
+        .ok_or_else(|| overflow::<T>(what, format!("{a} - {b}")))
+}
+
+pub fn mul<T: CheckedMul + Display>(what: &str, a: T, b: T) -> Result<T> {
+    if !enabled() {
+        return Ok(a * b);
+    }
+    a.checked_mul(&b)
+        .ok_or_else(|| overflow::<T>(what, format!("{a} * {b}")))
+}
+
+/// Division that, when checked, also insists there's no remainder.
+pub fn div<T: CheckedDiv + Integer + Display>(what: &str, a: T, b: T) -> Result<T> {
+    if !enabled() {
+        return Ok(a / b);
+    }
+    if b.is_zero() {
+        return Err(eyre!("{what}: {a} / {b} divides by zero"));
+    }
+    if !(a.mod_floor(&b)).is_zero() {
+        return Err(eyre!("{what}: {a} / {b} isn't exact"));
+    }
+    a.checked_div(&b)
+        .ok_or_else(|| overflow::<T>(what, format!("{a} / {b}")))
+}
+
+pub fn pow<T: CheckedMul + One + Clone + Display>(what: &str, base: T, exp: u32) -> Result<T> {
+    if !enabled() {
+        return Ok(num::pow(base, exp as usize));
+    }
+    num::checked_pow(base.clone(), exp as usize)
+        .ok_or_else(|| overflow::<T>(what, format!("{base}^{exp}")))
+}
@@ -0,0 +1,122 @@
+//! Checkpoints for the long-running days, so an interrupted run can pick up where it left off.
+//!
+//! A day saves its state through [`Checkpoint::save`] from points where the state is complete,
+//! like between rounds: whenever [`Checkpoint::due`] says it's been `--checkpoint-every SECS` (30
+//! by default, 0 for every chance) since the last save, or after a slow step like a blueprint.
+//! With `--resume` on the command line, [`Checkpoint::resume`] hands the last saved state back
+//! instead of starting over. The checkpoint is removed once the computation finishes.
+//!
+//! Checkpoints are kept per day, per computation, per input (with its parameters) and per solver
+//! version in `AOC_CHECKPOINT_DIR`, or `target/aoc-checkpoints` by default. The version is the
+//! same hash of the day binary [the cache](crate::cache) uses, so a rebuilt day never resumes from
+//! the state an older build saved. A checkpoint that doesn't parse is ignored.
+
+use crate::{
+    args::arg_seconds,
+    cache::{self, target_subdir, Key},
+    Puzzle,
+};
+use color_eyre::{eyre::Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+pub const CHECKPOINT_DIR_VAR: &str = "AOC_CHECKPOINT_DIR";
+
+pub fn resume_requested() -> bool {
+    env::args().any(|a| a == "--resume")
+}
+
+fn interval() -> Result<Duration> {
+    Ok(arg_seconds("--checkpoint-every")?.unwrap_or(Duration::from_secs(30)))
+}
+
+pub struct Checkpoint {
+    path: PathBuf,
+    every: Duration,
+    last_saved: Instant,
+}
+
+impl Checkpoint {
+    /// The checkpoint for computation `what` (e.g. `part2`) of the running day on `input`.
+    pub fn open(what: &str, input: &str, puzzle: &Puzzle) -> Result<Checkpoint> {
+        let exe = env::current_exe()?;
+        let day = exe
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut key = Key::default()
+            .with(cache::solver_version(&exe)?)
+            .with(input);
+        for (k, v) in puzzle.params() {
+            key = key.with(k).with(v);
+        }
+        let dir = match env::var_os(CHECKPOINT_DIR_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => target_subdir("aoc-checkpoints")?,
+        };
+        Ok(Checkpoint {
+            path: dir.join(format!("{day}-{what}-{}.json", key.finish())),
+            every: interval()?,
+            last_saved: Instant::now(),
+        })
+    }
+
+    /// The saved state, if `--resume` was given and there is one.
+    pub fn resume<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        if !resume_requested() {
+            return Ok(None);
+        }
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                eprintln!("no checkpoint at {}, starting over", self.path.display());
+                return Ok(None);
+            }
+            Err(e) => return Err(e).wrap_err_with(|| format!("reading {}", self.path.display())),
+        };
+        match serde_json::from_str(&text) {
+            Ok(state) => {
+                eprintln!("resuming from {}", self.path.display());
+                Ok(Some(state))
+            }
+            Err(e) => {
+                eprintln!("ignoring checkpoint {}: {e}", self.path.display());
+                Ok(None)
+            }
+        }
+    }
+
+    /// Whether it's been long enough since the last save for another one.
+    pub fn due(&self) -> bool {
+        self.last_saved.elapsed() >= self.every
+    }
+
+    pub fn save<T: Serialize>(&mut self, state: &T) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // write then rename, so being interrupted mid-save leaves the previous checkpoint
+        let partial = self.path.with_extension("partial");
+        fs::write(&partial, serde_json::to_string(state)?)
+            .wrap_err_with(|| format!("writing {}", partial.display()))?;
+        fs::rename(&partial, &self.path)?;
+        self.last_saved = Instant::now();
+        Ok(())
+    }
+
+    /// The computation finished; its checkpoint isn't needed any more.
+    pub fn done(self) -> Result<()> {
+        for path in [self.path.with_extension("partial"), self.path] {
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
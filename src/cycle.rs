@@ -0,0 +1,136 @@
+//! Finding where a simulation starts repeating itself, and skipping ahead with it.
+//!
+//! [`CycleFinder`] is for simulations whose state is big but can be boiled down to a key that
+//! decides everything after it (day17's surface, next jet and next rock). Each step is given
+//! its key and a metric to track (the stack height). Once a key comes round again and the
+//! following steps keep repeating for the requested number of confirmation periods, it hands
+//! back a [`Cycle`], which can work out the metric at any step from there on.
+//!
+//! [`brent`] is for small states that can be compared outright (day24's blizzards): it finds
+//! the prefix and period keeping only two states around.
+
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// steps before the cycle starts
+    pub start: usize,
+    pub period: usize,
+    /// the metric at steps `0..=start + period`
+    metrics: Vec<i64>,
+}
+
+impl Cycle {
+    /// How much the metric changes each time round the cycle.
+    pub fn delta(&self) -> i64 {
+        self.metrics[self.start + self.period] - self.metrics[self.start]
+    }
+
+    /// The metric at `step`, however far past what was simulated.
+    pub fn at(&self, step: usize) -> i64 {
+        if step < self.start {
+            return self.metrics[step];
+        }
+        let cycles = (step - self.start) / self.period;
+        let offset = (step - self.start) % self.period;
+        self.metrics[self.start + offset] + cycles as i64 * self.delta()
+    }
+}
+
+pub struct CycleFinder<K> {
+    confirmations: usize,
+    /// the latest step each key was seen at
+    seen: HashMap<K, usize>,
+    keys: Vec<K>,
+    metrics: Vec<i64>,
+    /// (start, period) of a repeat that hasn't been confirmed yet
+    candidate: Option<(usize, usize)>,
+    cycle: Option<Cycle>,
+}
+
+impl<K: Hash + Eq + Clone> CycleFinder<K> {
+    /// A finder that trusts a repeat once it has held for `confirmations` more whole periods.
+    pub fn new(confirmations: usize) -> CycleFinder<K> {
+        CycleFinder {
+            confirmations,
+            seen: HashMap::new(),
+            keys: vec![],
+            metrics: vec![],
+            candidate: None,
+            cycle: None,
+        }
+    }
+
+    /// Record the next step, returning the cycle once it's found.
+    pub fn step(&mut self, key: K, metric: i64) -> Option<&Cycle> {
+        if self.cycle.is_some() {
+            return self.cycle.as_ref();
+        }
+        let now = self.keys.len();
+        self.keys.push(key.clone());
+        self.metrics.push(metric);
+        if let Some((start, period)) = self.candidate {
+            let delta = self.metrics[start + period] - self.metrics[start];
+            let holds = self.keys[now] == self.keys[now - period]
+                && self.metrics[now] - self.metrics[now - period] == delta;
+            if !holds {
+                self.candidate = None;
+            } else if now >= start + period * (1 + self.confirmations) {
+                self.cycle = Some(Cycle {
+                    start,
+                    period,
+                    metrics: self.metrics[..=start + period].to_vec(),
+                });
+            }
+        }
+        if self.candidate.is_none() {
+            if let Some(&before) = self.seen.get(&key) {
+                self.candidate = Some((before, now - before));
+                if self.confirmations == 0 {
+                    self.cycle = Some(Cycle {
+                        start: before,
+                        period: now - before,
+                        metrics: self.metrics.clone(),
+                    });
+                }
+            }
+        }
+        self.seen.insert(key, now);
+        self.cycle.as_ref()
+    }
+
+    /// Every step recorded so far, oldest first; feeding them to a new finder recreates this one.
+    pub fn steps(&self) -> impl Iterator<Item = (&K, i64)> {
+        self.keys.iter().zip(self.metrics.iter().copied())
+    }
+}
+
+/// Brent's algorithm: how many steps from `start` until the states start repeating, and how
+/// often they repeat.
+pub fn brent<S: Clone + PartialEq>(start: &S, mut next: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = next(start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = next(&hare);
+        period += 1;
+    }
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    for _ in 0..period {
+        hare = next(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        prefix += 1;
+    }
+    (prefix, period)
+}
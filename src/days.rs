@@ -0,0 +1,164 @@
+//! What the `aoc` tool needs to know about each day's binary.
+
+use crate::exec;
+use color_eyre::{eyre::bail, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// How an input is put together, which decides the units the minimiser removes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputShape {
+    /// one record per line
+    Lines,
+    /// records separated by blank lines
+    Blocks,
+    /// a rectangular character grid
+    Grid,
+}
+
+/// How a day is told which part to solve.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Parts {
+    /// one run answers everything it can
+    Together,
+    /// `--part2` switches to part 2
+    Flag,
+    /// `PART2` in the environment switches to part 2
+    Env,
+}
+
+#[derive(Debug)]
+pub struct Day {
+    pub name: &'static str,
+    pub shape: InputShape,
+    pub parts: Parts,
+    /// whether the day has more than one way of solving it, named by `--impl list`
+    pub has_impls: bool,
+    /// the params it reads (from `--set` or the input's metadata) that are plain values, not
+    /// files, so `aoc serve` can let a request set them
+    pub params: &'static [&'static str],
+}
+
+const fn day(name: &'static str, shape: InputShape) -> Day {
+    Day {
+        name,
+        shape,
+        parts: Parts::Together,
+        has_impls: false,
+        params: &[],
+    }
+}
+
+const fn split(name: &'static str, shape: InputShape, parts: Parts) -> Day {
+    Day {
+        name,
+        shape,
+        parts,
+        has_impls: false,
+        params: &[],
+    }
+}
+
+const fn impls(name: &'static str, shape: InputShape) -> Day {
+    Day {
+        name,
+        shape,
+        parts: Parts::Together,
+        has_impls: true,
+        params: &[],
+    }
+}
+
+impl Day {
+    const fn params(self, params: &'static [&'static str]) -> Day {
+        Day { params, ..self }
+    }
+
+    /// A command for this day that solves `part`, or every part if it can't be picked or it's `None`.
+    pub fn command(&self, part: Option<u8>) -> Result<Command> {
+        let mut cmd = exec::day_command(self.name)?;
+        if part == Some(2) {
+            match self.parts {
+                Parts::Together => {}
+                Parts::Flag => {
+                    cmd.arg("--part2");
+                }
+                Parts::Env => {
+                    cmd.env("PART2", "1");
+                }
+            }
+        }
+        Ok(cmd)
+    }
+
+    /// The names of the day's implementations, default first, as it lists them itself.
+    pub fn impls(&self) -> Result<Vec<String>> {
+        if !self.has_impls {
+            return Ok(vec![]);
+        }
+        let mut cmd = self.command(None)?;
+        cmd.args(["--impl", "list"]);
+        let outcome = exec::run(cmd, b"", None)?;
+        if !outcome.success() {
+            bail!("{} --impl list failed:\n{}", self.name, outcome.stderr);
+        }
+        Ok(outcome.stdout.lines().map(str::to_owned).collect())
+    }
+
+    /// The inputs for this day under `dir` (`inputs/dayN/*.txt`), sorted by name.
+    pub fn inputs(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut inputs: Vec<PathBuf> = fs::read_dir(dir.join(self.name))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().map(|e| e == "txt").unwrap_or(false))
+                    .collect()
+            })
+            .unwrap_or_default();
+        inputs.sort();
+        inputs
+    }
+}
+
+use InputShape::*;
+use Parts::*;
+pub const DAYS: [Day; 25] = [
+    day("day1", Blocks),
+    day("day2", Lines),
+    day("day3", Lines).params(&["group_size"]),
+    day("day4", Lines),
+    split("day5", Lines, Flag),
+    impls("day6", Lines),
+    day("day7", Lines).params(&["total_space", "needed_space"]),
+    day("day8", Grid),
+    split("day9", Lines, Flag),
+    day("day10", Lines),
+    split("day11", Blocks, Flag).params(&["part1_rounds", "part2_rounds"]),
+    day("day12", Grid),
+    day("day13", Blocks),
+    split("day14", Lines, Env),
+    impls("day15", Lines).params(&["row", "search_max"]),
+    day("day16", Lines),
+    day("day17", Lines),
+    impls("day18", Lines),
+    day("day19", Lines),
+    day("day20", Lines).params(&["trace", "decryption_key", "mix_rounds"]),
+    day("day21", Lines),
+    day("day22", Lines).params(&["cube_net"]),
+    day("day23", Grid),
+    day("day24", Grid),
+    day("day25", Lines),
+];
+
+/// Look a day up by name (`day7`) or number (`7`).
+pub fn find(name: &str) -> Result<&'static Day> {
+    let num = name.strip_prefix("day").unwrap_or(name);
+    match DAYS.iter().find(|d| d.name[3..] == *num) {
+        Some(day) => Ok(day),
+        None => bail!("no such day: {name}"),
+    }
+}
@@ -0,0 +1,202 @@
+//! Grid directions, with y growing downwards as it does in the inputs.
+//!
+//! [`Dir4`] is the four orthogonal directions and [`Dir8`] adds the diagonals. Both number
+//! themselves clockwise from right/east starting at 0, which is the facing value day22's
+//! password wants, and parse from whatever letters or arrows an input uses.
+
+use color_eyre::{eyre::bail, Report, Result};
+use num::{CheckedAdd, CheckedSub, One};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Move one unit from `(x, y)` by `(dx, dy)` (each -1, 0 or 1), `None` if that overflows.
+fn offset_by<T>((x, y): (T, T), (dx, dy): (isize, isize)) -> Option<(T, T)>
+where
+    T: Copy + CheckedAdd + CheckedSub + One,
+{
+    let shift = |v: T, d: isize| match d {
+        1 => v.checked_add(&T::one()),
+        -1 => v.checked_sub(&T::one()),
+        _ => Some(v),
+    };
+    Some((shift(x, dx)?, shift(y, dy)?))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Dir4 {
+    Right = 0,
+    Down = 1,
+    Left = 2,
+    Up = 3,
+}
+
+impl Dir4 {
+    /// Clockwise from `Right`, in code order.
+    pub const ALL: [Dir4; 4] = [Dir4::Right, Dir4::Down, Dir4::Left, Dir4::Up];
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Dir4> {
+        Dir4::ALL.get(code as usize).copied()
+    }
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::ALL[(self.code() as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::ALL[(self.code() as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Dir4 {
+        Dir4::ALL[(self.code() as usize + 2) % 4]
+    }
+
+    /// The unit vector, as (dx, dy).
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Dir4::Right => (1, 0),
+            Dir4::Down => (0, 1),
+            Dir4::Left => (-1, 0),
+            Dir4::Up => (0, -1),
+        }
+    }
+
+    /// One step from `pos`, `None` if it would leave the type's range (off the top or left edge
+    /// for unsigned coordinates).
+    pub fn go<T>(self, pos: (T, T)) -> Option<(T, T)>
+    where
+        T: Copy + CheckedAdd + CheckedSub + One,
+    {
+        offset_by(pos, self.offset())
+    }
+
+    /// `U/D/L/R`, `^v<>` or `N/S/E/W`.
+    pub fn from_char(c: char) -> Option<Dir4> {
+        Some(match c {
+            'R' | '>' | 'E' => Dir4::Right,
+            'D' | 'v' | 'S' => Dir4::Down,
+            'L' | '<' | 'W' => Dir4::Left,
+            'U' | '^' | 'N' => Dir4::Up,
+            _ => None?,
+        })
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Dir4::Right => '>',
+            Dir4::Down => 'v',
+            Dir4::Left => '<',
+            Dir4::Up => '^',
+        }
+    }
+}
+
+impl FromStr for Dir4 {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Dir4> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(Dir4::from_char), chars.next()) {
+            (Some(dir), None) => Ok(dir),
+            _ => bail!("unknown direction {s:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Dir8 {
+    E = 0,
+    SE = 1,
+    S = 2,
+    SW = 3,
+    W = 4,
+    NW = 5,
+    N = 6,
+    NE = 7,
+}
+
+impl Dir8 {
+    /// Clockwise from `E`, in code order.
+    pub const ALL: [Dir8; 8] = [
+        Dir8::E,
+        Dir8::SE,
+        Dir8::S,
+        Dir8::SW,
+        Dir8::W,
+        Dir8::NW,
+        Dir8::N,
+        Dir8::NE,
+    ];
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Dir8> {
+        Dir8::ALL.get(code as usize).copied()
+    }
+
+    /// An eighth of a turn clockwise.
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self.code() as usize + 1) % 8]
+    }
+
+    /// An eighth of a turn anticlockwise.
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self.code() as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Dir8 {
+        Dir8::ALL[(self.code() as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.code() % 2 == 1
+    }
+
+    /// The unit vector (diagonals are one step in each direction), as (dx, dy).
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Dir8::E => (1, 0),
+            Dir8::SE => (1, 1),
+            Dir8::S => (0, 1),
+            Dir8::SW => (-1, 1),
+            Dir8::W => (-1, 0),
+            Dir8::NW => (-1, -1),
+            Dir8::N => (0, -1),
+            Dir8::NE => (1, -1),
+        }
+    }
+
+    /// One step from `pos`, `None` if it would leave the type's range.
+    pub fn go<T>(self, pos: (T, T)) -> Option<(T, T)>
+    where
+        T: Copy + CheckedAdd + CheckedSub + One,
+    {
+        offset_by(pos, self.offset())
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Dir8 {
+        Dir8::ALL[dir.code() as usize * 2]
+    }
+}
+
+impl FromStr for Dir8 {
+    type Err = Report;
+
+    /// A compass point (`N`, `NE`, ...), or anything [`Dir4`] parses.
+    fn from_str(s: &str) -> Result<Dir8> {
+        Ok(match s {
+            "NE" => Dir8::NE,
+            "SE" => Dir8::SE,
+            "SW" => Dir8::SW,
+            "NW" => Dir8::NW,
+            _ => Dir4::from_str(s)?.into(),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//! Event logs for the simulation days: record every state change, then replay up to any step.
+//!
+//! With `--record PATH` a day writes one JSON line per [`Record`]: a start event carrying the
+//! initial state, then each change as it happens (a grain of sand settling, a rock coming to
+//! rest, a round of elf moves, ...), numbered by step. With `--replay PATH [--step N]` the same
+//! day reads such a log back instead of an input, rebuilds the state as of step N (the end by
+//! default) from the events alone and shows it as `replay-step-N`.
+//!
+//! `aoc diff-events A B` compares two logs and reports the first event where they differ.
+
+use crate::{args::arg_value, render};
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record<E> {
+    pub step: u64,
+    pub event: E,
+}
+
+/// Writes the event log for `--record`, or nothing without it.
+pub struct Recorder {
+    out: Option<BufWriter<File>>,
+}
+
+impl Recorder {
+    pub fn open() -> Result<Recorder> {
+        let out = match arg_value::<String>("--record")? {
+            Some(path) => Some(BufWriter::new(
+                File::create(&path).wrap_err_with(|| format!("creating {path}"))?,
+            )),
+            None => None,
+        };
+        Ok(Recorder { out })
+    }
+
+    /// Whether events are being kept, for days where working them out costs something.
+    pub fn enabled(&self) -> bool {
+        self.out.is_some()
+    }
+
+    pub fn record(&mut self, step: u64, event: impl Serialize) -> Result<()> {
+        if let Some(out) = &mut self.out {
+            serde_json::to_writer(&mut *out, &Record { step, event })?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(out) = &mut self.out {
+            let _ = out.flush();
+        }
+    }
+}
+
+/// A `--replay` request: which log, and up to which step.
+pub struct Replay {
+    path: PathBuf,
+    step: Option<u64>,
+}
+
+impl Replay {
+    pub fn requested() -> Result<Option<Replay>> {
+        let Some(path) = arg_value("--replay")? else {
+            return Ok(None);
+        };
+        Ok(Some(Replay {
+            path,
+            step: arg_value("--step")?,
+        }))
+    }
+
+    /// The step asked for, if any.
+    pub fn step(&self) -> Option<u64> {
+        self.step
+    }
+
+    /// Every event in the log, for days that work out the requested step themselves.
+    pub fn all_events<E: DeserializeOwned>(&self) -> Result<Vec<Record<E>>> {
+        read(&self.path)
+    }
+
+    /// The log's events up to and including the requested step.
+    pub fn events<E: DeserializeOwned>(&self) -> Result<Vec<Record<E>>> {
+        let mut events = read(&self.path)?;
+        if let Some(step) = self.step {
+            events.retain(|r: &Record<E>| r.step <= step);
+        }
+        Ok(events)
+    }
+
+    pub fn show(&self, picture: impl Display) -> Result<()> {
+        let name = match self.step {
+            Some(step) => format!("replay-step-{step}"),
+            None => "replay".to_owned(),
+        };
+        render::show(&name, picture)
+    }
+}
+
+/// Every event in a log.
+pub fn read<E: DeserializeOwned>(path: &Path) -> Result<Vec<Record<E>>> {
+    let text = fs::read_to_string(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            serde_json::from_str(line).map_err(|e| eyre!("{}:{}: {e}", path.display(), idx + 1))
+        })
+        .collect()
+}
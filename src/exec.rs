@@ -0,0 +1,162 @@
+//! Running day binaries (or any other command) on an input and collecting what they did.
+
+use crate::puzzle::{AnswerRecord, ANSWERS_VAR};
+use color_eyre::{
+    eyre::{bail, Context},
+    Result,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    io::{Read, Write},
+    path::PathBuf,
+    process::{Command, ExitStatus, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Rust exits with this when main panics.
+const PANIC_EXIT_CODE: i32 = 101;
+
+#[derive(Debug)]
+pub struct Outcome {
+    /// `None` if the process was killed for running over its time limit
+    pub status: Option<ExitStatus>,
+    pub stdout: String,
+    pub stderr: String,
+    pub answers: BTreeMap<u8, String>,
+    /// when each answer came, counting from when the day loaded its puzzle
+    pub answered_at: BTreeMap<u8, Duration>,
+    /// parts whose answer is only the best a search found within `--time-budget`
+    pub unproven: BTreeSet<u8>,
+    pub elapsed: Duration,
+}
+
+impl Outcome {
+    pub fn timed_out(&self) -> bool {
+        self.status.is_none()
+    }
+    pub fn success(&self) -> bool {
+        self.status.map(|s| s.success()).unwrap_or(false)
+    }
+    pub fn panicked(&self) -> bool {
+        self.status.and_then(|s| s.code()) == Some(PANIC_EXIT_CODE)
+    }
+}
+
+/// Where the binary for `day` lives: the day binaries get built next to this one.
+pub fn day_exe(day: &str) -> Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let path = exe
+        .with_file_name(day)
+        .with_extension(env::consts::EXE_EXTENSION);
+    if !path.exists() {
+        bail!("{} doesn't exist, run cargo build first", path.display());
+    }
+    Ok(path)
+}
+
+pub fn day_command(day: &str) -> Result<Command> {
+    Ok(Command::new(day_exe(day)?))
+}
+
+/// A command run through `sh -c`, for user-supplied predicates and reference implementations.
+pub fn shell_command(script: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(script);
+    cmd
+}
+
+/// A fresh path in the temp directory for a scratch file or directory.
+pub fn scratch_path(what: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    env::temp_dir().join(format!("aoc2022-{}-{}-{}", what, std::process::id(), n))
+}
+
+/// Drop the colour escapes from a day's output (color_eyre reports are full of them).
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip an escape sequence up to its final letter
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut out = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut out);
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    })
+}
+
+/// Run `cmd` with `input` on stdin, killing it if it runs longer than `timeout`.
+pub fn run(mut cmd: Command, input: &[u8], timeout: Option<Duration>) -> Result<Outcome> {
+    let answers_path = scratch_path("answers");
+    let start = Instant::now();
+    let mut child = cmd
+        .env(ANSWERS_VAR, &answers_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err_with(|| format!("starting {:?}", cmd.get_program()))?;
+    let mut stdin = child.stdin.take();
+    let input = input.to_vec();
+    let writer = thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            // a day that stops reading early closes the pipe on us, that's fine
+            let _ = stdin.write_all(&input);
+        }
+    });
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if timeout.map(|t| start.elapsed() > t).unwrap_or(false) {
+            child.kill()?;
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+    let elapsed = start.elapsed();
+    let _ = writer.join();
+    let mut answers = BTreeMap::new();
+    let mut answered_at = BTreeMap::new();
+    let mut unproven = BTreeSet::new();
+    if let Ok(text) = fs::read_to_string(&answers_path) {
+        for line in text.lines() {
+            let record: AnswerRecord = serde_json::from_str(line)?;
+            answers.insert(record.part, record.answer);
+            if let Some(micros) = record.micros {
+                answered_at.insert(record.part, Duration::from_micros(micros));
+            }
+            if record.unproven {
+                unproven.insert(record.part);
+            }
+        }
+        fs::remove_file(&answers_path)?;
+    }
+    Ok(Outcome {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        answers,
+        answered_at,
+        unproven,
+        elapsed,
+    })
+}
@@ -0,0 +1,93 @@
+//! `--explain`: the work behind an answer.
+//!
+//! A day that supports it builds an [`Explanation`] for each part as it goes (the path it took,
+//! the timeline of actions it chose, the items that add up to the answer) and shows it next to
+//! the answer. Without `--explain` on the command line the steps are dropped as they're added,
+//! so the days don't need to check for it themselves.
+//!
+//! Explanations go through [`render::show`] as `explain-partN`, so `AOC_RENDER_DIR` captures
+//! them the same way as pictures.
+
+use crate::render;
+use color_eyre::Result;
+use std::fmt::{self, Display};
+
+pub fn requested() -> bool {
+    std::env::args().any(|a| a == "--explain")
+}
+
+pub struct Explanation {
+    part: u8,
+    enabled: bool,
+    /// titled groups of steps; the first one has no title when steps come before any section
+    sections: Vec<(Option<String>, Vec<String>)>,
+}
+
+impl Explanation {
+    pub fn new(part: u8) -> Explanation {
+        Explanation {
+            part,
+            enabled: requested(),
+            sections: vec![],
+        }
+    }
+
+    /// Whether anything will be shown, for days where working out the steps costs something.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start a new group of steps, e.g. one per blueprint.
+    pub fn section(&mut self, title: impl Display) {
+        if self.enabled {
+            self.sections.push((Some(title.to_string()), vec![]));
+        }
+    }
+
+    pub fn step(&mut self, text: impl Display) {
+        if self.enabled {
+            if self.sections.is_empty() {
+                self.sections.push((None, vec![]));
+            }
+            let (_, steps) = self.sections.last_mut().unwrap();
+            steps.push(text.to_string());
+        }
+    }
+
+    pub fn show(&self, answer: impl Display) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        render::show(
+            &format!("explain-part{}", self.part),
+            Shown {
+                explanation: self,
+                answer: answer.to_string(),
+            },
+        )
+    }
+}
+
+struct Shown<'a> {
+    explanation: &'a Explanation,
+    answer: String,
+}
+
+impl Display for Shown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "part {} = {}", self.explanation.part, self.answer)?;
+        for (title, steps) in &self.explanation.sections {
+            let indent = match title {
+                Some(title) => {
+                    writeln!(f, "  {title}")?;
+                    "    "
+                }
+                None => "  ",
+            };
+            for step in steps {
+                writeln!(f, "{indent}{step}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+//! Random inputs for the days that have a generator, sized by a rough count of records.
+//!
+//! They're for comparing implementations rather than for looking like the real thing, so they
+//! stay small and dense enough to hit the edge cases: markers at the very end of day6's
+//! stream, day15 sensors reaching exactly to the row, day18 pockets of air. `aoc profile` also
+//! uses them to see how a day copes as the inputs grow, which is what day20 and day22 have one for.
+
+use std::fmt::Write;
+
+/// xorshift64*, so a seed always gives the same inputs without pulling in a crate for it.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // xorshift never leaves zero
+        Rng(seed.wrapping_mul(0x9e3779b97f4a7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    /// A number in `lo..=hi`.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+}
+
+pub struct Generated {
+    pub input: String,
+    /// what the input's toml would set, as for `--set`
+    pub params: Vec<(String, String)>,
+}
+
+impl Generated {
+    fn plain(input: String) -> Generated {
+        Generated {
+            input,
+            params: vec![],
+        }
+    }
+}
+
+/// A random input for `day` of about `size` records, if it has a generator.
+pub fn generate(day: &str, size: usize, rng: &mut Rng) -> Option<Generated> {
+    Some(match day {
+        "day6" => day6(size, rng),
+        "day15" => day15(size, rng),
+        "day18" => day18(size, rng),
+        "day20" => day20(size, rng),
+        "day22" => day22(size, rng),
+        _ => return None,
+    })
+}
+
+/// Up to `size` characters with no marker in them, then fourteen different letters, then up to
+/// `size` more of anything (often nothing).
+fn day6(size: usize, rng: &mut Rng) -> Generated {
+    let mut line = String::new();
+    for _ in 0..rng.range(0, size as i64) {
+        line.push((b'a' + rng.range(0, 2) as u8) as char);
+    }
+    let mut letters: Vec<u8> = (b'a'..=b'z').collect();
+    for _ in 0..14 {
+        let idx = rng.range(0, letters.len() as i64 - 1) as usize;
+        line.push(letters.swap_remove(idx) as char);
+    }
+    for _ in 0..rng.range(-(size as i64), size as i64).max(0) {
+        line.push((b'a' + rng.range(0, 25) as u8) as char);
+    }
+    Generated::plain(line + "\n")
+}
+
+/// `size` sensors around a 20x20 search area, with row 10 as the part 1 row and a good share of
+/// the sensors right on it.
+fn day15(size: usize, rng: &mut Rng) -> Generated {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let sx = rng.range(-5, 25);
+        let sy = if rng.range(0, 3) == 0 {
+            10
+        } else {
+            rng.range(-5, 25)
+        };
+        let (bx, by) = (sx + rng.range(-6, 6), sy + rng.range(-6, 6));
+        writeln!(
+            input,
+            "Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}"
+        )
+        .unwrap();
+    }
+    Generated {
+        input,
+        params: vec![
+            ("row".to_owned(), "10".to_owned()),
+            ("search_max".to_owned(), "20".to_owned()),
+        ],
+    }
+}
+
+/// `size` cubes packed into a box about twice their volume.
+fn day18(size: usize, rng: &mut Rng) -> Generated {
+    let side = ((size.max(1) * 2) as f64).cbrt().ceil() as i64;
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let (x, y, z) = (rng.range(0, side), rng.range(0, side), rng.range(0, side));
+        writeln!(input, "{x},{y},{z}").unwrap();
+    }
+    Generated::plain(input)
+}
+
+/// `size` numbers, exactly one of them 0, some moving further than the list is long.
+fn day20(size: usize, rng: &mut Rng) -> Generated {
+    let size = size.max(1) as i64;
+    let zero = rng.range(0, size - 1);
+    let mut input = String::new();
+    for n in 0..size {
+        let number = match rng.range(-size * 3, size * 3) {
+            _ if n == zero => 0,
+            0 => 1,
+            other => other,
+        };
+        writeln!(input, "{number}").unwrap();
+    }
+    Generated::plain(input)
+}
+
+/// The example's cube net with faces about `sqrt(size)` across and a wall here and there, and
+/// a path of `size` moves over it.
+fn day22(size: usize, rng: &mut Rng) -> Generated {
+    let edge = ((size as f64).sqrt().ceil() as usize).max(4);
+    // which faces of a 4x3 grid of them the example's net uses, by row
+    let net: [&[usize]; 3] = [&[2], &[0, 1, 2], &[2, 3]];
+    let mut input = String::new();
+    for faces in net {
+        for _ in 0..edge {
+            let mut line = " ".repeat(faces[0] * edge);
+            for _ in 0..(faces.len() * edge) {
+                line.push(if rng.range(0, 9) == 0 { '#' } else { '.' });
+            }
+            input += &line;
+            input.push('\n');
+        }
+    }
+    // the path starts at the leftmost open tile of the top row, so there has to be one
+    input.replace_range(2 * edge..2 * edge + 1, ".");
+    input.push('\n');
+    for n in 0..size.max(1) {
+        if n > 0 {
+            input.push(if rng.range(0, 1) == 0 { 'L' } else { 'R' });
+        }
+        write!(input, "{}", rng.range(1, 2 * edge as i64)).unwrap();
+    }
+    input.push('\n');
+    Generated {
+        input,
+        params: vec![("cube_net".to_owned(), "example".to_owned())],
+    }
+}
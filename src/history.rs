@@ -0,0 +1,227 @@
+//! Stepping a simulation backwards as well as forwards.
+//!
+//! A [`History`] keeps the events a [`Reversible`] simulation produced, which is all it takes to
+//! undo or redo each of them, so the state can be wound back and forth without keeping copies
+//! of it. History steps are events rather than simulation steps: day14 counts grains of sand
+//! that came to rest, day23 rounds in which an elf moved.
+//!
+//! `--rewind` ends a run at a prompt on the terminal for doing that, and `--rewind-script PATH`
+//! reads the prompt's commands from a file instead:
+//!
+//! ```text
+//! b [N]       back N steps (1)
+//! f [N]       forward N steps (1)
+//! j N         jump to step N
+//! d A B       what changed between steps A and B
+//! s           show the state
+//! q           quit
+//! ```
+
+use crate::{args::arg_value, simulation::Simulation};
+use color_eyre::{
+    eyre::{bail, ensure, Context, ContextCompat},
+    Result,
+};
+use std::{
+    collections::BTreeSet,
+    env,
+    fmt::Debug,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+};
+
+pub trait Reversible: Simulation {
+    /// The pieces the state is made of, for diffs: occupied places, say.
+    type Cell: Ord + Clone + Debug;
+
+    /// Put the state back to before the step that produced `event`.
+    fn undo(&mut self, event: &Self::Event);
+
+    /// Make the step that produced `event` again.
+    fn redo(&mut self, event: &Self::Event);
+
+    fn cells(&self) -> BTreeSet<Self::Cell>;
+}
+
+/// What changed between two steps.
+pub struct Diff<C> {
+    pub removed: Vec<C>,
+    pub added: Vec<C>,
+}
+
+pub struct History<S: Reversible> {
+    events: Vec<S::Event>,
+    /// how many of the events the state has been through
+    at: usize,
+}
+
+impl<S: Reversible> Default for History<S> {
+    fn default() -> Self {
+        History {
+            events: vec![],
+            at: 0,
+        }
+    }
+}
+
+impl<S: Reversible> History<S> {
+    pub fn new() -> History<S> {
+        History::default()
+    }
+
+    /// Add the event the simulation just produced; it has to be at the end of its history.
+    pub fn record(&mut self, event: S::Event) {
+        assert_eq!(self.at, self.events.len(), "recording in the middle of history");
+        self.events.push(event);
+        self.at += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The step the state is at, from 0 (before the first event) to [`History::len`].
+    pub fn at(&self) -> usize {
+        self.at
+    }
+
+    /// Undo the last step, if there is one.
+    pub fn back(&mut self, sim: &mut S) -> bool {
+        if self.at == 0 {
+            return false;
+        }
+        self.at -= 1;
+        sim.undo(&self.events[self.at]);
+        true
+    }
+
+    /// Redo the next step, if there is one.
+    pub fn forward(&mut self, sim: &mut S) -> bool {
+        let Some(event) = self.events.get(self.at) else {
+            return false;
+        };
+        sim.redo(event);
+        self.at += 1;
+        true
+    }
+
+    pub fn jump(&mut self, sim: &mut S, to: usize) -> Result<()> {
+        ensure!(to <= self.len(), "there are only {} steps", self.len());
+        while self.at > to {
+            self.back(sim);
+        }
+        while self.at < to {
+            self.forward(sim);
+        }
+        Ok(())
+    }
+
+    /// What changed from step `from` to step `to`, leaving the state where it was.
+    pub fn diff(&mut self, sim: &mut S, from: usize, to: usize) -> Result<Diff<S::Cell>> {
+        let here = self.at;
+        self.jump(sim, from)?;
+        let before = sim.cells();
+        self.jump(sim, to)?;
+        let after = sim.cells();
+        self.jump(sim, here)?;
+        Ok(Diff {
+            removed: before.difference(&after).cloned().collect(),
+            added: after.difference(&before).cloned().collect(),
+        })
+    }
+}
+
+/// A `--rewind` request, with where its commands come from.
+pub struct Rewind {
+    commands: Box<dyn BufRead>,
+}
+
+impl Rewind {
+    pub fn requested() -> Result<Option<Rewind>> {
+        if let Some(path) = arg_value::<String>("--rewind-script")? {
+            let file = File::open(&path).wrap_err_with(|| format!("opening {path}"))?;
+            return Ok(Some(Rewind {
+                commands: Box::new(BufReader::new(file)),
+            }));
+        }
+        if env::args().any(|a| a == "--rewind") {
+            // stdin was the input, so ask the terminal
+            let tty = File::open("/dev/tty").wrap_err("--rewind needs a terminal")?;
+            return Ok(Some(Rewind {
+                commands: Box::new(BufReader::new(tty)),
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Take commands until `q` or the end of them, drawing the state with `show`.
+    pub fn prompt<S: Reversible>(
+        mut self,
+        sim: &mut S,
+        history: &mut History<S>,
+        show: impl Fn(&S) -> String,
+    ) -> Result<()> {
+        loop {
+            print!("step {}/{}> ", history.at(), history.len());
+            io::stdout().flush()?;
+            let mut line = String::new();
+            if self.commands.read_line(&mut line)? == 0 {
+                println!();
+                return Ok(());
+            }
+            match command(&line, sim, history, &show) {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) => println!("{e}"),
+            }
+        }
+    }
+}
+
+/// Carry out one prompt command, returning whether it was the last.
+fn command<S: Reversible>(
+    line: &str,
+    sim: &mut S,
+    history: &mut History<S>,
+    show: impl Fn(&S) -> String,
+) -> Result<bool> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let step = |idx: usize, default: Option<usize>| -> Result<usize> {
+        match words.get(idx) {
+            Some(word) => word.parse().wrap_err_with(|| format!("{word:?} isn't a step")),
+            None => default.context("which step?"),
+        }
+    };
+    match words.first().copied() {
+        None => {}
+        Some("q" | "quit") => return Ok(true),
+        Some("b" | "back") => {
+            for _ in 0..step(1, Some(1))? {
+                history.back(sim);
+            }
+        }
+        Some("f" | "forward") => {
+            for _ in 0..step(1, Some(1))? {
+                history.forward(sim);
+            }
+        }
+        Some("j" | "jump") => history.jump(sim, step(1, None)?)?,
+        Some("d" | "diff") => {
+            let diff = history.diff(sim, step(1, None)?, step(2, None)?)?;
+            for cell in &diff.removed {
+                println!("- {cell:?}");
+            }
+            for cell in &diff.added {
+                println!("+ {cell:?}");
+            }
+            println!("{} removed, {} added", diff.removed.len(), diff.added.len());
+        }
+        Some("s" | "show") => print!("{}", show(sim)),
+        Some(other) => bail!("unknown command {other:?}: b [N], f [N], j N, d A B, s or q"),
+    }
+    Ok(false)
+}
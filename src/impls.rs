@@ -0,0 +1,33 @@
+//! `--impl NAME`: pick between a day's implementations of the same thing.
+//!
+//! Some days keep the slow but obviously correct approach next to the clever one. A day lists
+//! them as `(name, function)` pairs, default first, and picks one with [`chosen`] before it reads
+//! any input. `--impl list` prints the names instead, which is how `aoc diff-impls` finds every
+//! implementation of the days [`crate::days`] marks as having them, to check that they agree.
+
+use crate::args::arg_value;
+use color_eyre::{eyre::bail, Result};
+
+/// The implementation asked for with `--impl`, or the first one. `--impl list` lists their names
+/// a line each and exits.
+pub fn chosen<F: Copy>(impls: &[(&str, F)]) -> Result<F> {
+    let Some(name) = arg_value::<String>("--impl")? else {
+        return Ok(impls[0].1);
+    };
+    if name == "list" {
+        for (name, _) in impls {
+            println!("{name}");
+        }
+        std::process::exit(0);
+    }
+    match impls.iter().find(|(n, _)| *n == name) {
+        Some((_, f)) => Ok(*f),
+        None => {
+            let names: Vec<&str> = impls.iter().map(|(n, _)| *n).collect();
+            bail!(
+                "no implementation {name:?}, pick one of {}",
+                names.join(", ")
+            )
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Sets of integers kept as the inclusive ranges they're made of.
+//!
+//! An [`IntervalSet`] stores its ranges sorted, with overlapping or touching ones merged, so
+//! the set operations walk ranges rather than numbers: day15 covers a row millions of cells wide
+//! with a few dozen of them.
+
+use num::PrimInt;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    /// sorted, disjoint, and never touching (they'd have been merged)
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { ranges: vec![] }
+    }
+
+    /// Add every number in `range` (nothing, if it's empty).
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (lo, hi) = range.into_inner();
+        if lo > hi {
+            return;
+        }
+        // the ranges that overlap or touch lo..=hi, so become part of it
+        let first = self
+            .ranges
+            .partition_point(|&(_, end)| end.checked_add(&T::one()).is_some_and(|e| e < lo));
+        let last = self
+            .ranges
+            .partition_point(|&(start, _)| hi.checked_add(&T::one()).is_none_or(|h| start <= h));
+        if first == last {
+            self.ranges.insert(first, (lo, hi));
+        } else {
+            let (start, end) = (self.ranges[first].0, self.ranges[last - 1].1);
+            self.ranges
+                .splice(first..last, [(lo.min(start), hi.max(end))]);
+        }
+    }
+
+    /// Take every number in `range` out.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (lo, hi) = range.into_inner();
+        if lo > hi {
+            return;
+        }
+        let first = self.ranges.partition_point(|&(_, end)| end < lo);
+        let last = self.ranges.partition_point(|&(start, _)| start <= hi);
+        if first == last {
+            return;
+        }
+        // the ends of the first and last ranges can stick out either side
+        let (start, end) = (self.ranges[first].0, self.ranges[last - 1].1);
+        let mut kept = vec![];
+        if start < lo {
+            kept.push((start, lo - T::one()));
+        }
+        if end > hi {
+            kept.push((hi + T::one(), end));
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn contains(&self, n: T) -> bool {
+        let idx = self.ranges.partition_point(|&(_, end)| end < n);
+        self.ranges.get(idx).is_some_and(|&(start, _)| start <= n)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many numbers are in the set. That's any number of `u64`s, but not every `u128`.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(lo, hi)| {
+                // hi - lo itself can overflow T
+                let width = match (lo.to_i128(), hi.to_i128()) {
+                    (Some(lo), Some(hi)) => hi.abs_diff(lo),
+                    // only u128 goes past i128
+                    _ => hi.to_u128().unwrap() - lo.to_u128().unwrap(),
+                };
+                width.checked_add(1).expect("too many numbers to count")
+            })
+            .sum()
+    }
+
+    /// The merged ranges, in order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(lo, hi)| lo..=hi)
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for range in other.ranges() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = vec![];
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(&&(a_lo, a_hi)), Some(&&(b_lo, b_hi))) = (a.peek(), b.peek()) {
+            let (lo, hi) = (a_lo.max(b_lo), a_hi.min(b_hi));
+            if lo <= hi {
+                ranges.push((lo, hi));
+            }
+            // whichever ends first can't meet anything further on
+            if a_hi < b_hi {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = self.clone();
+        for range in other.ranges() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    /// What `within` has that the set doesn't.
+    pub fn gaps(&self, within: RangeInclusive<T>) -> IntervalSet<T> {
+        IntervalSet::from(within).difference(self)
+    }
+
+    pub fn is_subset(&self, other: &IntervalSet<T>) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_superset(&self, other: &IntervalSet<T>) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &IntervalSet<T>) -> bool {
+        self.intersection(other).is_empty()
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
@@ -0,0 +1,24 @@
+//! Bits shared between the day binaries in `src/bin`.
+
+pub mod args;
+pub mod cache;
+pub mod check;
+pub mod checkpoint;
+pub mod cycle;
+pub mod checked;
+pub mod days;
+pub mod dir;
+pub mod events;
+pub mod exec;
+pub mod history;
+pub mod explain;
+pub mod generate;
+pub mod impls;
+pub mod interval;
+pub mod minimize;
+pub mod puzzle;
+pub mod render;
+pub mod search;
+pub mod simulation;
+
+pub use puzzle::Puzzle;
@@ -0,0 +1,91 @@
+//! Delta debugging: shrink an input while it stays "interesting".
+
+use crate::days::InputShape;
+use color_eyre::Result;
+
+/// Zeller's ddmin, over whatever units the input was split into.
+///
+/// `interesting` must hold for the full input. The result is 1-minimal: removing any single
+/// remaining unit makes it uninteresting.
+pub fn ddmin<T: Clone>(
+    units: Vec<T>,
+    mut interesting: impl FnMut(&[T]) -> Result<bool>,
+) -> Result<Vec<T>> {
+    let mut current = units;
+    let mut n = 2;
+    while current.len() >= 2 {
+        let chunk_len = current.len().div_ceil(n);
+        let chunks: Vec<_> = (0..current.len())
+            .step_by(chunk_len)
+            .map(|start| start..(start + chunk_len).min(current.len()))
+            .collect();
+        let mut reduced = None;
+        for chunk in &chunks {
+            let subset = current[chunk.clone()].to_vec();
+            if interesting(&subset)? {
+                reduced = Some((subset, 2));
+                break;
+            }
+        }
+        if reduced.is_none() && chunks.len() > 2 {
+            for chunk in &chunks {
+                let complement: Vec<T> = current[..chunk.start]
+                    .iter()
+                    .chain(&current[chunk.end..])
+                    .cloned()
+                    .collect();
+                if interesting(&complement)? {
+                    reduced = Some((complement, (n - 1).max(2)));
+                    break;
+                }
+            }
+        }
+        match reduced {
+            Some((smaller, nn)) => {
+                current = smaller;
+                n = nn;
+            }
+            None if n >= current.len() => break,
+            None => n = (n * 2).min(current.len()),
+        }
+    }
+    Ok(current)
+}
+
+/// Shrink `input` as a whole, one pass per kind of unit its shape allows.
+pub fn minimize(
+    input: &str,
+    shape: InputShape,
+    mut interesting: impl FnMut(&str) -> Result<bool>,
+) -> Result<String> {
+    let mut current = input.to_owned();
+    let sep = if shape == InputShape::Blocks { "\n\n" } else { "\n" };
+    let units: Vec<&str> = current.trim_end().split(sep).collect();
+    let before = units.len();
+    let kept = ddmin(units, |units| interesting(&join(units, sep)))?;
+    eprintln!("{:?}: {} -> {}", shape, before, kept.len());
+    current = join(&kept, sep);
+    if shape == InputShape::Grid {
+        // the rows are as few as they'll go, now drop whole columns
+        let rows: Vec<Vec<char>> = current.lines().map(|l| l.chars().collect()).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let keep_columns = |cols: &[usize]| -> String {
+            let mut out = String::new();
+            for row in &rows {
+                out.extend(cols.iter().filter_map(|&c| row.get(c)));
+                out.push('\n');
+            }
+            out
+        };
+        let cols = ddmin((0..width).collect(), |cols| interesting(&keep_columns(cols)))?;
+        eprintln!("Grid columns: {} -> {}", width, cols.len());
+        current = keep_columns(&cols);
+    }
+    Ok(current)
+}
+
+fn join(units: &[&str], sep: &str) -> String {
+    let mut out = units.join(sep);
+    out.push('\n');
+    out
+}
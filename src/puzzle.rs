@@ -0,0 +1,245 @@
+//! Per-input puzzle parameters and expected answers.
+//!
+//! An input `inputs/dayN/NAME.txt` can have a `NAME.toml` next to it declaring whatever the
+//! solution can't (or shouldn't) work out from the input itself, plus the known answers:
+//!
+//! ```toml
+//! [params]
+//! row = 10
+//! search_max = 20
+//!
+//! [answers]
+//! part1 = 26
+//! part2 = 56000011
+//! ```
+//!
+//! Inputs arrive on stdin, so the days find the file through `AOC_INPUT` (exported by `./run`
+//! and `./watch`) or an explicit `--meta PATH`. On the command line `--set key=value` overrides
+//! a parameter and `--expect part1=value` an answer. The file's answers only hold for its own
+//! parameters, so once `--set` changes one, only the `--expect`ed answers are checked.
+//!
+//! When `AOC_ANSWERS` names a file, every reported answer is also appended to it as a JSON line,
+//! which is how the `aoc` tool picks answers out of a day's otherwise free-form output. Each
+//! record carries how long after [`Puzzle::load`] it was made, for `aoc profile` to time the parts
+//! by, and whether it's [unproven](Puzzle::answer_searched).
+
+use color_eyre::{
+    eyre::{bail, eyre, Context, ContextCompat},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Instant,
+};
+
+pub const INPUT_VAR: &str = "AOC_INPUT";
+pub const ANSWERS_VAR: &str = "AOC_ANSWERS";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerRecord {
+    pub part: u8,
+    pub answer: String,
+    /// microseconds since the puzzle was loaded
+    #[serde(default)]
+    pub micros: Option<u64>,
+    /// the best a search found before `--time-budget` ran out, which may not be the answer
+    #[serde(default)]
+    pub unproven: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MetaFile {
+    #[serde(default)]
+    params: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    answers: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Default)]
+pub struct Puzzle {
+    meta_path: Option<PathBuf>,
+    params: BTreeMap<String, String>,
+    answers: BTreeMap<String, String>,
+    /// the params given with `--set` rather than by the file
+    params_by_args: Vec<String>,
+    /// the answers given with `--expect` rather than by the file
+    expected_by_args: Vec<String>,
+    loaded: Option<Instant>,
+}
+
+fn value_string(v: toml::Value) -> String {
+    match v {
+        toml::Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+fn split_assignment(arg: &str) -> Result<(String, String)> {
+    let (k, v) = arg
+        .split_once('=')
+        .with_context(|| format!("expected key=value, got {arg:?}"))?;
+    Ok((k.to_owned(), v.to_owned()))
+}
+
+/// The metadata file that goes with an input file, if there is one.
+pub fn meta_path_for(input: &Path) -> Option<PathBuf> {
+    let meta = input.with_extension("toml");
+    meta.exists().then_some(meta)
+}
+
+impl Puzzle {
+    /// Load the metadata for the current input, applying command line overrides.
+    pub fn load() -> Result<Puzzle> {
+        let loaded = Instant::now();
+        let args: Vec<String> = env::args().skip(1).collect();
+        let mut meta_path = env::var_os(INPUT_VAR).and_then(|i| meta_path_for(Path::new(&i)));
+        let mut overrides = vec![];
+        let mut expects = vec![];
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--meta" => meta_path = Some(args.next().context("--meta path")?.into()),
+                "--set" => overrides.push(split_assignment(args.next().context("--set")?)?),
+                "--expect" => expects.push(split_assignment(args.next().context("--expect")?)?),
+                _ => {}
+            }
+        }
+        let mut puzzle = match &meta_path {
+            Some(path) => Puzzle::from_file(path)?,
+            None => Puzzle::default(),
+        };
+        puzzle.expected_by_args = expects.iter().map(|(k, _)| k.clone()).collect();
+        for (key, value) in overrides {
+            puzzle.set_param(&key, value);
+        }
+        puzzle.answers.extend(expects);
+        puzzle.loaded = Some(loaded);
+        Ok(puzzle)
+    }
+
+    /// Override a param the way `--set` does, for a day's own option for it. If that changes
+    /// it, only the `--expect`ed answers still hold.
+    pub fn set_param(&mut self, key: &str, value: impl Display) {
+        let value = value.to_string();
+        if self.params.get(key) != Some(&value) {
+            let by_args = &self.expected_by_args;
+            self.answers.retain(|part, _| by_args.contains(part));
+        }
+        self.params_by_args.push(key.to_owned());
+        self.params.insert(key.to_owned(), value);
+    }
+
+    pub fn from_file(path: &Path) -> Result<Puzzle> {
+        let text = fs::read_to_string(path).wrap_err_with(|| format!("reading {path:?}"))?;
+        let meta: MetaFile = toml::from_str(&text).wrap_err_with(|| format!("parsing {path:?}"))?;
+        Ok(Puzzle {
+            meta_path: Some(path.to_owned()),
+            params: meta.params.into_iter().map(|(k, v)| (k, value_string(v))).collect(),
+            answers: meta.answers.into_iter().map(|(k, v)| (k, value_string(v))).collect(),
+            params_by_args: vec![],
+            expected_by_args: vec![],
+            loaded: None,
+        })
+    }
+
+    pub fn param<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.params
+            .get(key)
+            .map(|v| v.parse().map_err(|e| eyre!("bad value {v:?} for param {key}: {e}")))
+            .transpose()
+    }
+
+    pub fn param_or<T>(&self, key: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        Ok(self.param(key)?.unwrap_or(default))
+    }
+
+    /// A param naming a file. A relative path from the metadata file is taken from the directory
+    /// the file is in, one given with `--set` from the current directory.
+    pub fn param_path(&self, key: &str) -> Option<PathBuf> {
+        let path = Path::new(self.params.get(key)?);
+        match &self.meta_path {
+            Some(meta) if !self.params_by_args.iter().any(|k| k == key) => {
+                Some(meta.parent().unwrap_or(Path::new("")).join(path))
+            }
+            _ => Some(path.to_owned()),
+        }
+    }
+
+    pub fn params(&self) -> &BTreeMap<String, String> {
+        &self.params
+    }
+
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        self.answers.get(&format!("part{part}")).map(String::as_str)
+    }
+
+    fn record(&self, part: u8, answer: &str, unproven: bool) -> Result<()> {
+        if let Some(path) = env::var_os(ANSWERS_VAR) {
+            let record = AnswerRecord {
+                part,
+                answer: answer.to_owned(),
+                micros: self.loaded.map(|t| t.elapsed().as_micros() as u64),
+                unproven,
+            };
+            let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        }
+        Ok(())
+    }
+
+    /// Report the answer of a search, which is only known to be right if the search was
+    /// `complete`. One cut short by `--time-budget` isn't checked against the expected answer,
+    /// just marked unproven.
+    pub fn answer_searched(&self, part: u8, value: impl Display, complete: bool) -> Result<()> {
+        if complete {
+            return self.answer(part, value);
+        }
+        let got = value.to_string();
+        self.record(part, &got, true)?;
+        eprintln!(
+            "part {part} answer {got} is the best found within --time-budget, NOT proven optimal"
+        );
+        Ok(())
+    }
+
+    /// Report an answer, failing if it disagrees with the expected one.
+    pub fn answer(&self, part: u8, value: impl Display) -> Result<()> {
+        let got = value.to_string();
+        self.record(part, &got, false)?;
+        if let Some(expected) = self.expected(part) {
+            if got != expected {
+                bail!(
+                    "part {part} answer {got} doesn't match expected {expected} (from {})",
+                    self.source(part)
+                );
+            }
+            eprintln!("part {part} answer matches expected value");
+        }
+        Ok(())
+    }
+
+    fn source(&self, part: u8) -> String {
+        if self.expected_by_args.contains(&format!("part{part}")) {
+            return "--expect".to_owned();
+        }
+        match &self.meta_path {
+            Some(path) => path.display().to_string(),
+            None => "--expect".to_owned(),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Named pictures of puzzle state.
+//!
+//! Days that draw their state (the day10 CRT, the day14 sandbox, ...) hand the picture to [`show`]
+//! instead of printing it themselves. Normally it just gets printed, but when `AOC_RENDER_DIR` is
+//! set each picture is written to `<dir>/<name>.txt` instead, which is what the snapshot tests use.
+//! Pictures that are only progress output go through [`eshow`], which prints to stderr.
+
+use color_eyre::Result;
+use std::{env, fmt::Display, fs, path::PathBuf};
+
+pub const RENDER_DIR_VAR: &str = "AOC_RENDER_DIR";
+
+pub fn show(name: &str, picture: impl Display) -> Result<()> {
+    show_on(name, picture, false)
+}
+
+/// Like [`show`], but printed to stderr, to keep it out of the day's answer output.
+pub fn eshow(name: &str, picture: impl Display) -> Result<()> {
+    show_on(name, picture, true)
+}
+
+fn show_on(name: &str, picture: impl Display, stderr: bool) -> Result<()> {
+    let mut text = picture.to_string();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    match env::var_os(RENDER_DIR_VAR) {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(format!("{name}.txt")), text)?;
+        }
+        None if stderr => eprint!("{text}"),
+        None => print!("{text}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Instrumentation for the days that search (day16, day19, day24), and `--time-budget`.
+//!
+//! A day counts what its search does through a [`Search`]: every node it takes off the frontier,
+//! every node it throws away, and every time it finds a better answer. With `--search-stats` on
+//! the command line that's reported on stderr about once a second while the search runs, and
+//! summed up (with the best answer over time) when it's done.
+//!
+//! `--time-budget SECS` gives each search that long. Once it's used up [`Search::expand`] says
+//! stop, and the day answers with the best it found so far through
+//! [`Puzzle::answer_searched`](crate::Puzzle::answer_searched), which marks it as not proven
+//! optimal.
+
+use crate::args::arg_seconds;
+use color_eyre::{eyre::eyre, Result};
+use std::{
+    fmt::Display,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+struct Options {
+    stats: bool,
+    budget: Option<Duration>,
+}
+
+fn parse_options() -> Result<Options> {
+    Ok(Options {
+        stats: std::env::args().any(|a| a == "--search-stats"),
+        budget: arg_seconds("--time-budget")?,
+    })
+}
+
+fn options() -> Result<&'static Options> {
+    static OPTIONS: OnceLock<Result<Options, String>> = OnceLock::new();
+    OPTIONS
+        .get_or_init(|| parse_options().map_err(|e| format!("{e:#}")))
+        .as_ref()
+        .map_err(|e| eyre!("{e}"))
+}
+
+/// How often to look at the clock, in nodes expanded.
+const CLOCK_EVERY: u64 = 4096;
+const REPORT_EVERY: Duration = Duration::from_secs(1);
+/// How many of the latest improvements the summary lists.
+const TIMELINE_SHOWN: usize = 10;
+
+pub struct Search {
+    name: String,
+    stats: bool,
+    started: Instant,
+    deadline: Option<Instant>,
+    next_report: Instant,
+    expanded: u64,
+    pruned: u64,
+    frontier: usize,
+    max_frontier: usize,
+    /// each improvement on the best answer, and when it was found
+    best: Vec<(Duration, String)>,
+    out_of_time: bool,
+}
+
+impl Search {
+    /// Fails if `--time-budget` isn't a number of seconds.
+    pub fn new(name: impl Display) -> Result<Search> {
+        let options = options()?;
+        let started = Instant::now();
+        Ok(Search {
+            name: name.to_string(),
+            stats: options.stats,
+            started,
+            deadline: options.budget.map(|budget| started + budget),
+            next_report: started + REPORT_EVERY,
+            expanded: 0,
+            pruned: 0,
+            frontier: 0,
+            max_frontier: 0,
+            best: vec![],
+            out_of_time: false,
+        })
+    }
+
+    /// Count a node taken off the frontier, which has `frontier` nodes left on it. Returns false
+    /// once the time budget is used up, and the search should stop with what it has.
+    pub fn expand(&mut self, frontier: usize) -> bool {
+        self.expanded += 1;
+        self.frontier = frontier;
+        self.max_frontier = self.max_frontier.max(frontier);
+        if self.expanded.is_multiple_of(CLOCK_EVERY) {
+            let now = Instant::now();
+            if self.deadline.map(|d| now >= d).unwrap_or(false) {
+                self.out_of_time = true;
+            }
+            if self.stats && now >= self.next_report {
+                eprintln!("{}", self.progress());
+                self.next_report = now + REPORT_EVERY;
+            }
+        }
+        !self.out_of_time
+    }
+
+    /// Count a node dropped without expanding it: already seen, or can't beat the best.
+    pub fn prune(&mut self) {
+        self.pruned += 1;
+    }
+
+    pub fn improved(&mut self, best: impl Display) {
+        self.best.push((self.started.elapsed(), best.to_string()));
+    }
+
+    /// Whether the search ran to the end, so its best answer is the real one.
+    pub fn complete(&self) -> bool {
+        !self.out_of_time
+    }
+
+    fn progress(&self) -> String {
+        let best = match self.best.last() {
+            Some((_, best)) => best.as_str(),
+            None => "none yet",
+        };
+        format!(
+            "{}: {:.2?}, {} expanded, {} pruned, frontier {} (max {}), best {best}",
+            self.name,
+            self.started.elapsed(),
+            self.expanded,
+            self.pruned,
+            self.frontier,
+            self.max_frontier,
+        )
+    }
+
+    /// Report how the search went, and return whether it was complete.
+    pub fn finish(&self) -> bool {
+        if self.stats {
+            let ended = if self.out_of_time {
+                "out of time"
+            } else {
+                "done"
+            };
+            eprintln!("{} ({ended})", self.progress());
+            let hidden = self.best.len().saturating_sub(TIMELINE_SHOWN);
+            let mut timeline: Vec<String> = self.best[hidden..]
+                .iter()
+                .map(|(at, best)| format!("{best} at {at:.2?}"))
+                .collect();
+            if hidden > 0 {
+                timeline.insert(0, format!("({hidden} earlier)"));
+            }
+            if !timeline.is_empty() {
+                eprintln!("{}: best over time: {}", self.name, timeline.join(", "));
+            }
+        }
+        if self.out_of_time {
+            eprintln!(
+                "{}: stopped by --time-budget, best found is not proven optimal",
+                self.name
+            );
+        }
+        self.complete()
+    }
+}
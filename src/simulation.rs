@@ -0,0 +1,124 @@
+//! Step-by-step simulations and a runner for them.
+//!
+//! A [`Simulation`] is a state that knows how to take one step and when it has nothing left to
+//! do. The [`Runner`] steps it, for a number of steps or until a condition holds, and shows each
+//! step to its observers: they record events, take measurements, draw pictures, and can stop the
+//! run early (day17 stops once its cycle finder has seen enough).
+
+use color_eyre::Result;
+use std::ops::ControlFlow;
+
+pub trait Simulation {
+    /// What a step can report to the observers, like a grain of sand coming to rest.
+    type Event;
+
+    /// Take one step, returning the event it produced, if any.
+    fn step(&mut self) -> Option<Self::Event>;
+
+    /// Whether there's nothing more to simulate. Checked before every step.
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+type Observer<'a, S> =
+    Box<dyn FnMut(&S, u64, Option<&<S as Simulation>::Event>) -> Result<ControlFlow<()>> + 'a>;
+
+pub struct Runner<'a, S: Simulation> {
+    steps: u64,
+    observers: Vec<Observer<'a, S>>,
+}
+
+impl<'a, S: Simulation> Default for Runner<'a, S> {
+    fn default() -> Self {
+        Runner {
+            steps: 0,
+            observers: vec![],
+        }
+    }
+}
+
+impl<'a, S: Simulation> Runner<'a, S> {
+    pub fn new() -> Runner<'a, S> {
+        Runner::default()
+    }
+
+    /// Called after every step with the state, the number of steps taken and the step's event.
+    /// Breaking stops the run after the current step (the other observers still see it).
+    pub fn observe(
+        mut self,
+        observer: impl FnMut(&S, u64, Option<&S::Event>) -> Result<ControlFlow<()>> + 'a,
+    ) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Called with every event a step produces.
+    pub fn on_event(
+        mut self,
+        mut observer: impl FnMut(&S, u64, &S::Event) -> Result<()> + 'a,
+    ) -> Self {
+        self.observers.push(Box::new(move |sim, step, event| {
+            if let Some(event) = event {
+                observer(sim, step, event)?;
+            }
+            Ok(ControlFlow::Continue(()))
+        }));
+        self
+    }
+
+    /// Called after every `n`th step, for measurements and pictures along the way.
+    pub fn every(mut self, n: u64, mut observer: impl FnMut(&S, u64) -> Result<()> + 'a) -> Self {
+        self.observers.push(Box::new(move |sim, step, _| {
+            if step.is_multiple_of(n) {
+                observer(sim, step)?;
+            }
+            Ok(ControlFlow::Continue(()))
+        }));
+        self
+    }
+
+    /// Called once, after step `at`.
+    pub fn at(mut self, at: u64, mut observer: impl FnMut(&S) -> Result<()> + 'a) -> Self {
+        self.observers.push(Box::new(move |sim, step, _| {
+            if step == at {
+                observer(sim)?;
+            }
+            Ok(ControlFlow::Continue(()))
+        }));
+        self
+    }
+
+    /// Steps taken so far, over every run.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Step until `stop` holds (checked before each step), the simulation finishes or an
+    /// observer stops it. Returns the steps taken so far.
+    pub fn run_until(&mut self, sim: &mut S, mut stop: impl FnMut(&S, u64) -> bool) -> Result<u64> {
+        while !sim.finished() && !stop(sim, self.steps) {
+            let event = sim.step();
+            self.steps += 1;
+            let mut stopped = false;
+            for observer in &mut self.observers {
+                stopped |= observer(sim, self.steps, event.as_ref())?.is_break();
+            }
+            if stopped {
+                break;
+            }
+        }
+        Ok(self.steps)
+    }
+
+    /// Step until the simulation finishes or an observer stops it.
+    pub fn run(&mut self, sim: &mut S) -> Result<u64> {
+        self.run_until(sim, |_, _| false)
+    }
+
+    /// Take (at most) `n` more steps.
+    pub fn run_n(&mut self, sim: &mut S, n: u64) -> Result<u64> {
+        let end = self.steps + n;
+        self.run_until(sim, |_, steps| steps >= end)
+    }
+}
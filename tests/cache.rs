@@ -0,0 +1,60 @@
+//! `aoc run` answers from the cache the second time round, unless told not to.
+
+use std::{path::PathBuf, process::Command};
+
+fn aoc(cache_dir: &PathBuf, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("AOC_CACHE_DIR", cache_dir)
+        .args(args)
+        .output()
+        .expect("running aoc");
+    assert!(
+        output.status.success(),
+        "aoc {args:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn second_run_is_cached() {
+    let cache_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("aoc-cache-test");
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let run = ["run", "day1", "inputs/day1/example.txt"];
+
+    let first = aoc(&cache_dir, &run);
+    assert!(first.contains("day1 part 1: 24000"), "{first}");
+    assert!(!first.contains("cached"), "{first}");
+
+    let second = aoc(&cache_dir, &run);
+    assert!(second.contains("day1 part 1: 24000 (cached"), "{second}");
+    assert!(second.contains("day1 part 2: 45000 (cached"), "{second}");
+
+    let uncached = aoc(&cache_dir, &[&run[..], &["--no-cache"]].concat());
+    assert!(!uncached.contains("cached"), "{uncached}");
+
+    let cleared = aoc(&cache_dir, &["cache-clear"]);
+    assert!(cleared.contains("removed 1 entries"), "{cleared}");
+    assert!(!aoc(&cache_dir, &run).contains("cached"));
+}
+
+#[test]
+fn unproven_answers_arent_cached() {
+    let cache_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("aoc-cache-unproven");
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    // input.toml expects the real answers, which a search cut short at once won't find
+    let run = [
+        "run",
+        "day19",
+        "inputs/day19/input.txt",
+        "--",
+        "--time-budget",
+        "0",
+    ];
+    for _ in 0..2 {
+        let output = aoc(&cache_dir, &run);
+        assert!(output.contains("NOT proven optimal"), "{output}");
+        assert!(!output.contains("cached"), "{output}");
+    }
+}
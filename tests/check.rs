@@ -0,0 +1,133 @@
+//! `--check` accepts every input we have and points at what's wrong with broken ones.
+
+use aoc2022::{
+    check::{self, Problems},
+    days::DAYS,
+};
+use common::{day_command, manifest_path, run_on, run_piped};
+use std::process::Output;
+
+mod common;
+
+fn check(day: &str, input: &str) -> Output {
+    run_piped(day_command(day).arg("--check"), input)
+}
+
+/// The example with `edit` applied to each of its numbered lines.
+fn broken_example(day: &str, edit: impl Fn(usize, &str) -> Option<String>) -> String {
+    let path = manifest_path(&format!("inputs/{day}/example.txt"));
+    let example = std::fs::read_to_string(path).unwrap();
+    check::lines(&example)
+        .map(|(lnum, line)| edit(lnum, line).unwrap_or_else(|| line.to_owned()) + "\n")
+        .collect()
+}
+
+/// Check `input` and return the problems it reported, which there must be.
+fn problems(day: &str, input: &str) -> Vec<String> {
+    let output = check(day, input);
+    assert!(!output.status.success(), "{day} --check passed");
+    let problems: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_owned)
+        .collect();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("{} problem(s) in input", problems.len())),
+        "{stderr}"
+    );
+    problems
+}
+
+#[test]
+fn numbering() {
+    let input = "a\n\n\nb\nc\n\nd\n\n";
+    assert_eq!(
+        check::lines(input).collect::<Vec<_>>(),
+        [
+            (1, "a"),
+            (2, ""),
+            (3, ""),
+            (4, "b"),
+            (5, "c"),
+            (6, ""),
+            (7, "d"),
+            (8, "")
+        ]
+    );
+    assert_eq!(
+        check::blocks(input),
+        [vec![(1, "a")], vec![(4, "b"), (5, "c")], vec![(7, "d")]]
+    );
+}
+
+#[test]
+fn problems_fail_the_report() {
+    assert!(Problems::new().report().is_ok());
+
+    let mut problems = Problems::new();
+    assert_eq!(problems.parse::<u32>(1, "12", "a number"), Some(12));
+    assert!(problems.is_empty());
+    assert_eq!(problems.parse::<u32>(2, "x", "a number"), None);
+    problems.grid([(3, "ab"), (4, "abc"), (5, "a?")], |c| {
+        c.is_ascii_lowercase()
+    });
+    problems.input("too short");
+    let err = problems.report().unwrap_err();
+    assert_eq!(err.to_string(), "4 problem(s) in input");
+}
+
+#[test]
+fn our_inputs_pass() {
+    for day in &DAYS {
+        for input in day.inputs(&manifest_path("inputs")) {
+            let output = run_on(day_command(day.name).arg("--check"), &input);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(output.status.success(), "{}: {stdout}", input.display());
+            assert_eq!(stdout, "input ok\n", "{}", input.display());
+        }
+    }
+}
+
+#[test]
+fn day3_bad_item() {
+    let input = broken_example("day3", |lnum, line| {
+        (lnum == 2).then(|| line.replacen('j', "1", 1))
+    });
+    assert_eq!(
+        problems("day3", &input),
+        ["line 2: unexpected '1' at column 1"]
+    );
+}
+
+#[test]
+fn day12_without_start_or_end() {
+    let input = broken_example("day12", |lnum, line| match lnum {
+        1 => Some(line.replace('S', "a")),
+        3 => Some(line.replace('E', "z")[1..].to_owned()),
+        _ => None,
+    });
+    assert_eq!(
+        problems("day12", &input),
+        [
+            "line 3: row is 7 wide, expected 8",
+            "input: expected one S marker, found 0",
+            "input: expected one E marker, found 0",
+        ]
+    );
+}
+
+#[test]
+fn day16_without_aa() {
+    let input = broken_example("day16", |_, line| {
+        Some(line.replace("Valve AA", "Valve AB"))
+    });
+    assert_eq!(
+        problems("day16", &input),
+        [
+            r#"line 2: tunnel to undefined valve "AA""#,
+            r#"line 4: tunnel to undefined valve "AA""#,
+            r#"line 9: tunnel to undefined valve "AA""#,
+            "input: no valve AA to start from",
+        ]
+    );
+}
@@ -0,0 +1,60 @@
+//! `--checked` catches what plain arithmetic would get wrong, and changes nothing else.
+
+use aoc2022::{exec, puzzle::INPUT_VAR};
+use common::{day_command, manifest_path};
+use std::fs;
+
+mod common;
+
+fn day(name: &str, args: &[&str], input: &str) -> exec::Outcome {
+    let mut cmd = day_command(name);
+    cmd.args(args);
+    exec::run(cmd, input.as_bytes(), None).unwrap()
+}
+
+#[test]
+fn day21_inexact_division() {
+    let input = "root: aaaa + bbbb\naaaa: cccc / dddd\ncccc: 7\ndddd: 2\n\
+                 bbbb: humn * eeee\neeee: 2\nhumn: 5\n";
+    let plain = day("day21", &[], input);
+    assert!(plain.success(), "{}", plain.stderr);
+    // 7 / 2 truncates to 3
+    assert_eq!(plain.answers[&1], "13");
+
+    let checked = day("day21", &["--checked"], input);
+    let stderr = exec::strip_ansi(&checked.stderr);
+    assert!(!checked.success());
+    assert!(stderr.contains("monkey aaaa"), "{stderr}");
+    assert!(stderr.contains("7 / 2 isn't exact"), "{stderr}");
+}
+
+#[test]
+fn day25_overflow() {
+    // 5^27 still fits an i64, twice it doesn't
+    let checked = day("day25", &["--checked"], &"2".repeat(28));
+    let stderr = exec::strip_ansi(&checked.stderr);
+    assert!(!checked.success());
+    assert!(!checked.panicked(), "{stderr}");
+    assert!(
+        stderr.contains("SNAFU digit: 2 * 7450580596923828125 overflows i64"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn same_answers_either_way() {
+    for name in ["day11", "day15", "day20", "day21", "day25"] {
+        let path = manifest_path(&format!("inputs/{name}/example.txt"));
+        let input = fs::read_to_string(&path).unwrap();
+        let run = |args: &[&str]| {
+            let mut cmd = day_command(name);
+            cmd.args(args).env(INPUT_VAR, &path);
+            let outcome = exec::run(cmd, input.as_bytes(), None).unwrap();
+            assert!(outcome.success(), "{name} {args:?}: {}", outcome.stderr);
+            outcome.answers
+        };
+        let plain = run(&[]);
+        assert!(!plain.is_empty(), "{name}");
+        assert_eq!(plain, run(&["--checked"]), "{name}");
+    }
+}
@@ -0,0 +1,131 @@
+//! A day interrupted part way through picks up from its checkpoint with `--resume`, as long as
+//! it's still the same build.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+fn checkpoints(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .map(|e| e.unwrap().path())
+                .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Start `cmd`, saving a checkpoint as often as it can, and kill it once there is one.
+fn interrupt(cmd: &mut Command, dir: &Path) {
+    let mut first = cmd
+        .args(["--checkpoint-every", "0"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    while checkpoints(dir).is_empty() {
+        assert!(
+            first.try_wait().unwrap().is_none(),
+            "finished before a checkpoint"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+    first.kill().unwrap();
+    first.wait().unwrap();
+}
+
+#[test]
+fn resume_after_interruption() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("checkpoints");
+    let _ = fs::remove_dir_all(&dir);
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs/day11/input.txt");
+    let day = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_day11"));
+        cmd.args(["--part2"])
+            .args(args)
+            .env("AOC_CHECKPOINT_DIR", &dir)
+            .env("AOC_INPUT", &input)
+            .stdin(fs::File::open(&input).unwrap());
+        cmd
+    };
+
+    interrupt(&mut day(&[]), &dir);
+
+    let output = day(&["--resume"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stdout.contains("Resuming after round"), "{stdout}");
+    assert!(stderr.contains("part 2 answer matches"), "{stderr}");
+    assert!(checkpoints(&dir).is_empty(), "checkpoint left behind");
+}
+
+#[test]
+fn only_the_same_build_resumes() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("checkpoints-day20");
+    let _ = fs::remove_dir_all(&dir);
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs/day20/input.txt");
+    // the same code, but not the same binary
+    let other_build = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("other-build");
+    fs::create_dir_all(&other_build).unwrap();
+    let other_exe = other_build.join("day20");
+    let mut exe = fs::read(env!("CARGO_BIN_EXE_day20")).unwrap();
+    exe.push(0);
+    fs::write(&other_exe, exe).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&other_exe, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let day = |exe: &Path, args: &[&str]| {
+        let mut cmd = Command::new(exe);
+        cmd.args(args)
+            .env("AOC_CHECKPOINT_DIR", &dir)
+            .env("AOC_INPUT", &input)
+            .stdin(fs::File::open(&input).unwrap());
+        cmd
+    };
+
+    interrupt(&mut day(&other_exe, &[]), &dir);
+    let output = day(Path::new(env!("CARGO_BIN_EXE_day20")), &["--resume"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("starting over"), "{stderr}");
+    // the other build's checkpoint is still there for it
+    assert_eq!(checkpoints(&dir).len(), 1);
+
+    let output = day(&other_exe, &["--resume"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stdout.contains("resuming after mixing round"), "{stdout}");
+    assert!(stderr.contains("part 2 answer matches"), "{stderr}");
+    assert!(checkpoints(&dir).is_empty(), "checkpoint left behind");
+}
+
+#[test]
+fn bad_interval() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("checkpoints-bad-interval");
+    for every in ["-1", "soon"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_day11"))
+            .args(["--part2", "--checkpoint-every", every])
+            .env("AOC_CHECKPOINT_DIR", &dir)
+            .stdin(fs::File::open("inputs/day11/example.txt").unwrap())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(!stderr.contains("panicked"), "{stderr}");
+        assert!(
+            stderr.contains(&format!("bad value \"{every}\" for --checkpoint-every")),
+            "{stderr}"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Running the binaries, for the integration tests.
+
+// each test crate uses only some of these
+#![allow(dead_code)]
+
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+/// A day binary picked at run time; they get built next to aoc. Tests of one particular day
+/// use its `CARGO_BIN_EXE_dayN` instead.
+pub fn day_command(day: &str) -> Command {
+    Command::new(Path::new(env!("CARGO_BIN_EXE_aoc")).with_file_name(day))
+}
+
+/// A path in the repo, like `inputs/day1/example.txt`.
+pub fn manifest_path(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+/// Run `cmd` with `input` on its stdin.
+pub fn run_piped(cmd: &mut Command, input: &str) -> Output {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("running day");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Run `cmd` on an input file the way `./run` does: on its stdin, and named by `AOC_INPUT` so
+/// the day finds the file's metadata.
+pub fn run_on(cmd: &mut Command, input: &Path) -> Output {
+    cmd.env("AOC_INPUT", input)
+        .stdin(File::open(input).unwrap())
+        .output()
+        .expect("running day")
+}
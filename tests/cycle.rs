@@ -0,0 +1,62 @@
+//! The cycle finders agree with a sequence whose cycle is known.
+
+use aoc2022::cycle::{self, CycleFinder};
+
+/// 0, 1, 2, then 3, 4, 5, 6 over and over, with the metric going up 10 each time round.
+fn key(step: usize) -> usize {
+    if step < 3 {
+        step
+    } else {
+        3 + (step - 3) % 4
+    }
+}
+
+fn metric(step: usize) -> i64 {
+    if step < 3 {
+        step as i64
+    } else {
+        let (cycles, offset) = ((step - 3) / 4, (step - 3) % 4);
+        3 + offset as i64 * offset as i64 + cycles as i64 * 10
+    }
+}
+
+#[test]
+fn finds_and_extrapolates() {
+    let mut finder = CycleFinder::new(1);
+    let mut found = None;
+    for step in 0..100 {
+        if let Some(cycle) = finder.step(key(step), metric(step)) {
+            // confirmed one whole period after the first repeat at step 7
+            assert_eq!(step, 11);
+            found = Some(cycle.clone());
+            break;
+        }
+    }
+    let cycle = found.expect("cycle found");
+    assert_eq!((cycle.start, cycle.period, cycle.delta()), (3, 4, 10));
+    for step in [0, 2, 3, 6, 50, 1001] {
+        assert_eq!(cycle.at(step), metric(step), "step {step}");
+    }
+}
+
+#[test]
+fn unconfirmed_repeat_is_dropped() {
+    // the key repeats once by accident before the real cycle shows up
+    let keys = [0, 1, 0, 2, 3, 4, 2, 3, 4, 2, 3, 4];
+    let mut finder = CycleFinder::new(1);
+    let found = keys
+        .iter()
+        .enumerate()
+        .find_map(|(step, &key)| finder.step(key, step as i64).cloned())
+        .expect("cycle found");
+    assert_eq!((found.start, found.period, found.delta()), (3, 3, 3));
+}
+
+#[test]
+fn brent_matches() {
+    assert_eq!(
+        cycle::brent(&0, |&k| if k == 6 { 3 } else { k + 1 }),
+        (3, 4)
+    );
+    assert_eq!(cycle::brent(&5u32, |&n| (n * 3) % 7), (0, 6));
+}
@@ -0,0 +1,78 @@
+//! day1 ranks the elves by any aggregate and copes with too few of them.
+
+use common::run_piped;
+use std::process::{Command, Output};
+
+mod common;
+
+fn day1(args: &[&str], input: &str) -> Output {
+    run_piped(Command::new(env!("CARGO_BIN_EXE_day1")).args(args), input)
+}
+
+const EXAMPLE: &str = include_str!("../inputs/day1/example.txt");
+
+#[test]
+fn top_k_by_aggregate() {
+    let output = day1(&["--top", "2", "--by", "count"], EXAMPLE);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let elves: Vec<&str> = stdout
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("elf "))
+        .map(|l| l.split(':').next().unwrap())
+        .collect();
+    // elves 1 and 4 both have 3 items, and ties go to the earlier elf
+    assert_eq!(elves, ["1", "4"], "{stdout}");
+    assert!(stdout.contains("top3 total: 45000"), "{stdout}");
+}
+
+#[test]
+fn too_few_elves() {
+    let output = day1(&[], "1000\n2000\n\n3000\n");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("max sum: 3000"), "{stdout}");
+    assert!(stderr.contains("only 2 elves"), "{stderr}");
+}
+
+#[test]
+fn stats_report() {
+    let output = day1(&["--stats"], EXAMPLE);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let row = |name: &str| -> Vec<String> {
+        let line = stdout.lines().find(|l| l.starts_with(name)).unwrap();
+        line.split_whitespace().map(str::to_owned).collect()
+    };
+    // count, min, p10, p25, median, p75, p90, max, mean, stddev
+    assert_eq!(
+        row("calories")[1..6],
+        ["5", "4000.0", "4800.0", "6000.0", "10000.0"]
+    );
+    assert_eq!(
+        row("items")[1..],
+        ["5", "1.0", "1.0", "1.0", "2.0", "3.0", "3.0", "3.0", "2.0", "0.9"]
+    );
+    let bars: usize = stdout.lines().filter(|l| l.contains('|')).count();
+    assert_eq!(bars, 10, "{stdout}");
+    assert!(stdout.contains("1 outliers"), "{stdout}");
+    assert!(
+        stdout.contains("  elf 4: 24000 calories in 3 items"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn options_need_their_values() {
+    for (args, error) in [
+        (&["--top", "--stats"][..], "--top needs a value"),
+        (&["--by"][..], "--by needs a value"),
+        (&["--top", "many"][..], "bad value \"many\" for --top"),
+    ] {
+        let output = day1(args, EXAMPLE);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(error), "{stderr}");
+    }
+}
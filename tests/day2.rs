@@ -0,0 +1,111 @@
+//! day2 plays whatever game its rules file describes, once the rules hold together.
+
+use common::{manifest_path, run_on};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+mod common;
+
+fn day2(args: &[&str], input: &Path) -> Output {
+    run_on(
+        Command::new(env!("CARGO_BIN_EXE_day2"))
+            .args(args)
+            // away from the repo, so nothing can rely on paths relative to it
+            .current_dir(env!("CARGO_TARGET_TMPDIR")),
+        input,
+    )
+}
+
+#[test]
+fn rock_paper_scissors_lizard_spock() {
+    let output = day2(&[], &manifest_path("inputs/day2/rpsls.txt"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("part 1 answer matches"), "{stderr}");
+    assert!(stderr.contains("part 2 answer matches"), "{stderr}");
+}
+
+#[test]
+fn inconsistent_rules() {
+    let rules = fs::read_to_string(manifest_path("inputs/day2/rules/rpsls.toml")).unwrap();
+    // paper now beats itself instead of rock
+    let broken = rules.replace(
+        r#"beats = ["rock", "spock"]"#,
+        r#"beats = ["paper", "spock"]"#,
+    );
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("day2-broken-rules.toml");
+    fs::write(&path, broken).unwrap();
+    let output = day2(
+        &["--set", &format!("rules={}", path.display())],
+        &manifest_path("inputs/day2/example.txt"),
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("paper beats itself"), "{stderr}");
+    assert!(
+        stderr.contains("nothing decides rock against paper"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn ciphers() {
+    let output = day2(&["--ciphers"], &manifest_path("inputs/day2/example.txt"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    // 3! orders of the moves and 3! ways of giving each outcome one code
+    assert!(stdout.starts_with("12 ways"), "{stdout}");
+    let total = |reading: &str| -> Vec<String> {
+        let line = stdout.lines().find(|l| l.contains(reading)).unwrap();
+        line.split_whitespace().skip(5).map(str::to_owned).collect()
+    };
+    assert_eq!(
+        total("as moves X=rock Y=paper Z=scissors"),
+        ["15", "(part", "1)"]
+    );
+    assert_eq!(
+        total("as outcomes X=lose Y=draw Z=win"),
+        ["12", "(part", "2)"]
+    );
+    assert!(
+        stdout.contains("best:  as moves X=scissors Y=paper Z=rock scores 24\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("worst: as moves X=rock Y=scissors Z=paper scores 6\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn round_report_and_csv() {
+    let csv = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("day2-rounds.csv");
+    let output = day2(
+        &["--rounds", "--csv", csv.to_str().unwrap()],
+        &manifest_path("inputs/day2/example.txt"),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|l| l.split_whitespace().filter(|w| *w != "|").collect())
+        .collect();
+    assert!(rows.contains(&vec![
+        "1", "rock", "Y", "paper", "win", "2", "6", "rock", "draw", "1", "3"
+    ]));
+    assert!(rows.contains(&vec!["as", "moves", "1", "1", "1", "1", "1", "1", "15"]));
+    assert!(rows.contains(&vec!["as", "goals", "1", "1", "1", "3", "0", "0", "12"]));
+
+    let csv = fs::read_to_string(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("round,opponent,code,part1_move,part1_outcome,"));
+    assert_eq!(lines[3], "3,scissors,Z,scissors,draw,3,3,rock,win,1,6");
+}
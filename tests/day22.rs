@@ -0,0 +1,43 @@
+//! day22 part 2 walks across the example cube's edges the way they fold.
+
+use common::run_piped;
+use std::process::Command;
+
+mod common;
+
+/// The example's net without walls. Faces are numbered in reading order.
+const OPEN_NET: &str = "        ....
+        ....
+        ....
+        ....
+............
+............
+............
+............
+        ........
+        ........
+        ........
+        ........
+";
+
+#[test]
+fn crosses_the_top_edges() {
+    // up off face 0 onto face 1 heading down, turn around and up off face 1 back onto face 0
+    // heading down; then down to face 4, right onto face 5 and up off it onto face 3's right
+    // side, heading left at row 7 column 12
+    let input = format!("{OPEN_NET}\nL1R0R11L5L3\n");
+    let output = run_piped(
+        Command::new(env!("CARGO_BIN_EXE_day22")).args([
+            "--set",
+            "cube_net=example",
+            "--expect",
+            "part2=7050",
+        ]),
+        &input,
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
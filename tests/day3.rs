@@ -0,0 +1,79 @@
+//! day3 finds each group's badge however many rucksacks make up a group.
+
+use common::{manifest_path, run_on};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+mod common;
+
+fn day3(args: &[&str], input: &Path) -> Output {
+    run_on(Command::new(env!("CARGO_BIN_EXE_day3")).args(args), input)
+}
+
+fn example() -> PathBuf {
+    manifest_path("inputs/day3/example.txt")
+}
+
+#[test]
+fn groups_of_two() {
+    let output = day3(&["--group-size", "2"], &example());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // badges f, q and G
+    assert!(stdout.contains("Total missort priority: 157\n"), "{stdout}");
+    assert!(stdout.contains("Total badge priority: 56\n"), "{stdout}");
+}
+
+/// The example's first four rucksacks, in a file of their own for each test.
+fn four_rucksacks(test: &str) -> PathBuf {
+    let example = fs::read_to_string(example()).unwrap();
+    let first_four: String = example.lines().take(4).map(|l| format!("{l}\n")).collect();
+    let input = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("day3-{test}.txt"));
+    fs::write(&input, first_four).unwrap();
+    input
+}
+
+#[test]
+fn incomplete_last_group() {
+    let input = four_rucksacks("incomplete");
+    let answers = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("day3-four-rucksacks.jsonl");
+    let _ = fs::remove_file(&answers);
+    let output = run_on(
+        Command::new(env!("CARGO_BIN_EXE_day3")).env("AOC_ANSWERS", &answers),
+        &input,
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("last group only has 1 of 3 rucksacks"),
+        "{stderr}"
+    );
+    // part 1 doesn't care about groups, so it's answered all the same
+    let answers = fs::read_to_string(answers).unwrap();
+    assert!(answers.contains(r#""part":1,"answer":"118""#), "{answers}");
+}
+
+#[test]
+fn check_counts_the_last_group() {
+    let input = four_rucksacks("check");
+    let output = day3(&["--check"], &input);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("input: last group only has 1 of 3 rucksacks"),
+        "{stdout}"
+    );
+    let output = day3(&["--check", "--group-size", "2"], &input);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,60 @@
+//! The direction types turn, reverse, parse and number themselves consistently.
+
+use aoc2022::dir::{Dir4, Dir8};
+
+#[test]
+fn dir4_algebra() {
+    for dir in Dir4::ALL {
+        assert_eq!(dir.turn_right().turn_left(), dir);
+        assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+        assert_eq!(Dir4::from_code(dir.code()), Some(dir));
+        let (dx, dy) = dir.offset();
+        assert_eq!(dir.reverse().offset(), (-dx, -dy));
+        assert_eq!(Dir4::from_char(dir.arrow()), Some(dir));
+    }
+    assert_eq!(Dir4::Right.turn_right(), Dir4::Down);
+    assert_eq!(Dir4::Up.code(), 3);
+    assert_eq!(Dir4::from_code(4), None);
+}
+
+#[test]
+fn dir4_parsing() {
+    for (text, dir) in [
+        ("U", Dir4::Up),
+        ("v", Dir4::Down),
+        ("W", Dir4::Left),
+        (">", Dir4::Right),
+    ] {
+        assert_eq!(text.parse::<Dir4>().unwrap(), dir);
+    }
+    assert!("X".parse::<Dir4>().is_err());
+    assert!("UU".parse::<Dir4>().is_err());
+}
+
+#[test]
+fn dir8_algebra() {
+    for dir in Dir8::ALL {
+        assert_eq!(dir.turn_right().turn_left(), dir);
+        assert_eq!(dir.reverse().reverse(), dir);
+        assert_eq!(Dir8::from_code(dir.code()), Some(dir));
+        let (dx, dy) = dir.offset();
+        assert_eq!(dx.abs() + dy.abs() == 2, dir.is_diagonal());
+    }
+    for dir in Dir4::ALL {
+        assert_eq!(Dir8::from(dir).offset(), dir.offset());
+        assert_eq!(
+            Dir8::from(dir.turn_right()),
+            Dir8::from(dir).turn_right().turn_right()
+        );
+    }
+    assert_eq!("NE".parse::<Dir8>().unwrap(), Dir8::NE);
+    assert_eq!("S".parse::<Dir8>().unwrap(), Dir8::S);
+}
+
+#[test]
+fn going_off_the_edge() {
+    assert_eq!(Dir4::Up.go((3usize, 0usize)), None);
+    assert_eq!(Dir4::Right.go((3usize, 0usize)), Some((4, 0)));
+    assert_eq!(Dir8::NW.go((-1isize, 5isize)), Some((-2, 4)));
+    assert_eq!(Dir4::Right.go((u16::MAX, 0)), None);
+}
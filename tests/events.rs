@@ -0,0 +1,158 @@
+//! A `--record` log replays to the same state the day drew while it ran.
+
+use common::{day_command, manifest_path};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+mod common;
+
+fn run(cmd: &mut Command) -> String {
+    let output = cmd.output().expect("running");
+    assert!(
+        output.status.success(),
+        "{cmd:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn snapshot(name: &str) -> String {
+    fs::read_to_string(manifest_path("tests/snapshots").join(name)).unwrap()
+}
+
+/// Records `day` running on its example into a scratch directory named `case`, which the
+/// day's own pictures go to as well, and returns the directory.
+fn record(day: &str, case: &str, env: &[(&str, &str)]) -> PathBuf {
+    let scratch = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("events")
+        .join(case);
+    let _ = fs::remove_dir_all(&scratch);
+    fs::create_dir_all(&scratch).unwrap();
+    let input = manifest_path(&format!("inputs/{day}/example.txt"));
+    run(day_command(day)
+        .arg("--record")
+        .arg(scratch.join("log.jsonl"))
+        .envs(env.iter().copied())
+        .env("AOC_RENDER_DIR", scratch.join("run"))
+        .stdin(fs::File::open(&input).unwrap()));
+    scratch
+}
+
+/// What `day` draws replaying the log in `scratch`, up to `step` or to the end.
+fn replay(day: &str, scratch: &Path, step: Option<u64>) -> String {
+    let mut cmd = day_command(day);
+    cmd.arg("--replay").arg(scratch.join("log.jsonl"));
+    if let Some(step) = step {
+        cmd.args(["--step", &step.to_string()]);
+    }
+    run(cmd.env("AOC_RENDER_DIR", scratch.join("replay")));
+    let name = match step {
+        Some(step) => format!("replay-step-{step}.txt"),
+        None => "replay.txt".to_owned(),
+    };
+    fs::read_to_string(scratch.join("replay").join(name)).unwrap()
+}
+
+fn steps(scratch: &Path) -> Vec<u64> {
+    fs::read_to_string(scratch.join("log.jsonl"))
+        .unwrap()
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["step"]
+                .as_u64()
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn replay_matches_snapshot() {
+    let scratch = record("day23", "day23", &[]);
+    assert_eq!(
+        replay("day23", &scratch, Some(10)),
+        snapshot("day23_example/round-10.txt")
+    );
+
+    let log = scratch.join("log.jsonl");
+    let diff = run(Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .arg("diff-events")
+        .arg(&log)
+        .arg(&log));
+    assert!(diff.contains("logs agree"), "{diff}");
+}
+
+#[test]
+fn day9_replays_the_rope_and_its_trail() {
+    let scratch = record("day9", "day9", &[]);
+    // the example's last picture, less the start marker
+    let end = "\
+..##.
+...##
+.TH##
+....#
+####.
+";
+    assert_eq!(replay("day9", &scratch, None), end);
+    assert_eq!(replay("day9", &scratch, Some(0)), "H\n");
+}
+
+#[test]
+fn day14_replays_the_sandbox_it_drew() {
+    for (case, env, snap) in [
+        ("day14", &[][..], "day14_example/sandbox.txt"),
+        (
+            "day14-part2",
+            &[("PART2", "1")][..],
+            "day14_example_part2/sandbox.txt",
+        ),
+    ] {
+        let scratch = record("day14", case, env);
+        let drawn = fs::read_to_string(scratch.join("run/sandbox.txt")).unwrap();
+        assert_eq!(drawn, snapshot(snap));
+        assert_eq!(replay("day14", &scratch, None), drawn, "{case}");
+    }
+}
+
+#[test]
+fn day17_replays_the_chamber_it_drew() {
+    let scratch = record("day17", "day17", &[]);
+    let drawn = fs::read_to_string(scratch.join("run/chamber-10.txt")).unwrap();
+    assert_eq!(drawn, snapshot("day17_example/chamber-10.txt"));
+    assert_eq!(replay("day17", &scratch, Some(10)), drawn);
+}
+
+#[test]
+fn day24_replays_each_minute_at_its_own_step() {
+    let scratch = record("day24", "day24", &[]);
+    let steps = steps(&scratch);
+    assert_eq!(
+        steps.iter().collect::<HashSet<_>>().len(),
+        steps.len(),
+        "{steps:?}"
+    );
+    let walls = "\
+#.######
+#......#
+#......#
+#......#
+#......#
+######.#
+";
+    let minute0 = fs::read_to_string(manifest_path("inputs/day24/example.txt")).unwrap();
+    // as the puzzle draws minute 1
+    let minute1 = "\
+#.######
+#.>3.<.#
+#<..<<.#
+#>2.22.#
+#>v..^<#
+######.#
+";
+    assert_eq!(replay("day24", &scratch, Some(0)), walls);
+    assert_eq!(replay("day24", &scratch, Some(1)), minute0);
+    assert_eq!(replay("day24", &scratch, Some(2)), minute1);
+}
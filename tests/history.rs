@@ -0,0 +1,43 @@
+//! Rewinding a finished run gets back to the states the day drew while it ran.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+#[test]
+fn rewind_matches_snapshots() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let scratch = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history");
+    let _ = fs::remove_dir_all(&scratch);
+    fs::create_dir_all(&scratch).unwrap();
+    let script = scratch.join("commands");
+    // there and back again before each look
+    fs::write(&script, "j 10\nb 7\nf 7\ns\nj 0\nj 19\ns\nd 0 19\nq\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_day23"))
+        .arg("--rewind-script")
+        .arg(&script)
+        .env("AOC_RENDER_DIR", scratch.join("run"))
+        .stdin(fs::File::open(manifest_dir.join("inputs/day23/example.txt")).unwrap())
+        .output()
+        .expect("running");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let snapshot = |name: &str| {
+        fs::read_to_string(
+            manifest_dir
+                .join("tests/snapshots/day23_example")
+                .join(name),
+        )
+        .unwrap()
+    };
+    let shown = |step: usize, name: &str| {
+        let prompt = format!("step {step}/19> ");
+        format!("{prompt}{}{prompt}", snapshot(name))
+    };
+    assert!(stdout.contains(&shown(10, "round-10.txt")), "{stdout}");
+    assert!(stdout.contains(&shown(19, "final.txt")), "{stdout}");
+    assert!(stdout.contains("18 removed, 18 added"), "{stdout}");
+}
@@ -0,0 +1,77 @@
+//! Every day's implementations agree with each other on generated inputs, and when two don't,
+//! `aoc diff-impls` says so and keeps the input.
+
+use std::{fs, os::unix::fs::PermissionsExt, path::PathBuf, process::Command};
+
+#[test]
+fn implementations_agree() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["diff-impls", "--no-inputs", "--generated", "20"])
+        .output()
+        .expect("running aoc diff-impls");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for line in [
+        "day6: popcount, naive agree",
+        "day15: intervals, brute agree",
+        "day18: bfs, flood agree",
+    ] {
+        assert!(stdout.contains(line), "{stdout}");
+    }
+}
+
+#[test]
+fn days_list_their_impls() {
+    let output = Command::new(env!("CARGO_BIN_EXE_day15"))
+        .args(["--impl", "list"])
+        .output()
+        .expect("running day15");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "intervals\nbrute\n"
+    );
+}
+
+/// A day6 whose two implementations answer differently.
+const DISAGREEING_DAY6: &str = r#"#!/bin/sh
+case "$2" in
+list) printf 'right\nwrong\n' ;;
+right) echo '{"part":1,"answer":"7"}' >> "$AOC_ANSWERS" ;;
+wrong) echo '{"part":1,"answer":"8"}' >> "$AOC_ANSWERS" ;;
+esac
+"#;
+
+#[test]
+fn disagreements_are_caught() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("disagreeing-impls");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("inputs/day6")).unwrap();
+    let aoc = dir.join("aoc");
+    fs::copy(env!("CARGO_BIN_EXE_aoc"), &aoc).unwrap();
+    let day6 = dir.join("day6");
+    fs::write(&day6, DISAGREEING_DAY6).unwrap();
+    fs::set_permissions(&day6, fs::Permissions::from_mode(0o755)).unwrap();
+    let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n";
+    fs::write(dir.join("inputs/day6/sample.txt"), input).unwrap();
+
+    let output = Command::new(&aoc)
+        .args(["diff-impls", "day6", "--generated", "0", "--inputs"])
+        .arg(dir.join("inputs"))
+        .output()
+        .expect("running aoc diff-impls");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
+    assert!(stdout.contains("implementations disagree"), "{stdout}");
+    assert!(stdout.contains("right: part 1 = 7"), "{stdout}");
+    assert!(stdout.contains("wrong: part 1 = 8"), "{stdout}");
+    let saved = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("input saved to "))
+        .expect("the input is saved");
+    assert_eq!(fs::read_to_string(saved).unwrap(), input);
+}
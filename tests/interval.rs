@@ -0,0 +1,78 @@
+//! IntervalSet agrees with a plain set of the same numbers.
+
+use aoc2022::interval::IntervalSet;
+use std::collections::BTreeSet;
+
+/// Random-ish ranges, some empty, some touching or overlapping the others.
+fn ranges(seed: i32) -> Vec<std::ops::RangeInclusive<i32>> {
+    (0..8)
+        .map(|n| {
+            let lo = (seed * 7 + n * 13) % 40 - 5;
+            lo..=lo + (seed + n * 5) % 9 - 1
+        })
+        .collect()
+}
+
+fn numbers(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+    set.ranges().flatten().collect()
+}
+
+#[test]
+fn merges_touching_ranges() {
+    let set: IntervalSet<i32> = [5..=7, 1..=3, 4..=4, 10..=12, 11..=11]
+        .into_iter()
+        .collect();
+    assert_eq!(set.ranges().collect::<Vec<_>>(), [1..=7, 10..=12]);
+    assert_eq!(set.len(), 10);
+    assert!(set.contains(4) && set.contains(10) && !set.contains(8) && !set.contains(0));
+    assert_eq!(
+        set.gaps(0..=13).ranges().collect::<Vec<_>>(),
+        [0..=0, 8..=9, 13..=13]
+    );
+}
+
+#[test]
+fn agrees_with_btreeset() {
+    for seed in 0..50 {
+        let a: IntervalSet<i32> = ranges(seed).into_iter().collect();
+        let b: IntervalSet<i32> = ranges(seed + 17).into_iter().collect();
+        let (na, nb) = (numbers(&a), numbers(&b));
+        let expect: BTreeSet<i32> = ranges(seed).into_iter().flatten().collect();
+        assert_eq!(na, expect, "seed {seed}");
+        assert_eq!(a.len(), na.len() as u128, "seed {seed}");
+        assert_eq!(numbers(&a.union(&b)), &na | &nb, "seed {seed}");
+        assert_eq!(numbers(&a.intersection(&b)), &na & &nb, "seed {seed}");
+        assert_eq!(numbers(&a.difference(&b)), &na - &nb, "seed {seed}");
+        assert_eq!(a.is_subset(&b), na.is_subset(&nb), "seed {seed}");
+        assert_eq!(a.is_disjoint(&b), na.is_disjoint(&nb), "seed {seed}");
+        // merged all the way down: no two ranges touch
+        for (r, s) in a.ranges().zip(a.ranges().skip(1)) {
+            assert!(r.end() + 1 < *s.start(), "seed {seed}: {r:?} {s:?}");
+        }
+        for n in -10..50 {
+            assert_eq!(a.contains(n), na.contains(&n), "seed {seed}, {n}");
+        }
+    }
+}
+
+#[test]
+fn reaches_the_ends_of_the_type() {
+    let mut set: IntervalSet<u8> = (250..=255).into();
+    set.insert(0..=3);
+    set.insert(4..=249);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), [0..=255]);
+    set.remove(0..=0);
+    set.remove(255..=255);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), [1..=254]);
+    assert_eq!(set.len(), 254);
+}
+
+#[test]
+fn counts_the_whole_type() {
+    assert_eq!(IntervalSet::from(i32::MIN..=i32::MAX).len(), 1 << 32);
+    assert_eq!(IntervalSet::from(u64::MIN..=u64::MAX).len(), 1 << 64);
+    assert_eq!(IntervalSet::from(i64::MIN..=i64::MAX).len(), 1 << 64);
+    let mut set = IntervalSet::from(i64::MIN..=-1);
+    set.insert(1..=i64::MAX);
+    assert_eq!(set.len(), u64::MAX as u128);
+}
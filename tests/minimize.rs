@@ -0,0 +1,93 @@
+//! ddmin shrinks to a 1-minimal input, and `aoc minimize` shrinks a real day's input with it.
+
+use aoc2022::{
+    days::InputShape,
+    minimize::{ddmin, minimize},
+};
+use std::{fs, path::PathBuf, process::Command};
+
+/// Shrink `units` and check that what's left is still interesting, but not without any one unit.
+fn assert_one_minimal(units: Vec<u32>, interesting: impl Fn(&[u32]) -> bool) -> Vec<u32> {
+    let mut tests = 0;
+    let kept = ddmin(units, |units| {
+        tests += 1;
+        Ok(interesting(units))
+    })
+    .unwrap();
+    assert!(interesting(&kept), "{kept:?} isn't interesting");
+    for skip in 0..kept.len() {
+        let without: Vec<u32> = [&kept[..skip], &kept[skip + 1..]].concat();
+        assert!(!interesting(&without), "{kept:?} isn't 1-minimal");
+    }
+    assert!(tests > 0);
+    kept
+}
+
+#[test]
+fn finds_the_units_that_matter() {
+    let kept = assert_one_minimal((0..50).collect(), |units| {
+        units.contains(&3) && units.contains(&17) && units.contains(&41)
+    });
+    assert_eq!(kept, [3, 17, 41]);
+}
+
+#[test]
+fn one_minimal_not_minimum() {
+    // any four odd numbers will do, or 40 on its own
+    let interesting =
+        |units: &[u32]| units.contains(&40) || units.iter().filter(|&&u| u % 2 == 1).count() >= 4;
+    for len in [8, 9, 16, 41] {
+        let kept = assert_one_minimal((0..len).collect(), interesting);
+        assert!(kept.len() == 4 || kept == [40], "{kept:?}");
+    }
+}
+
+#[test]
+fn keeps_everything_when_nothing_can_go() {
+    let kept = assert_one_minimal((0..7).collect(), |units| units.len() == 7);
+    assert_eq!(kept, (0..7).collect::<Vec<_>>());
+}
+
+#[test]
+fn grid_drops_rows_then_columns() {
+    let grid = "....\n.X..\n..Y.\n#XY#\n....\n";
+    // an X somewhere left of a Y in the same row
+    let minimized = minimize(grid, InputShape::Grid, |input| {
+        Ok(input
+            .lines()
+            .any(|l| matches!((l.find('X'), l.find('Y')), (Some(x), Some(y)) if x < y)))
+    })
+    .unwrap();
+    assert_eq!(minimized, "XY\n");
+}
+
+#[test]
+fn aoc_minimize_panics() {
+    let example = fs::read_to_string(format!(
+        "{}/inputs/day18/example.txt",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+    .unwrap();
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    // a cube with no commas in the middle of the example
+    let (first, rest) = example.split_at(example.len() / 2);
+    let rest = rest.split_once('\n').unwrap();
+    let input = dir.join("minimize-day18.txt");
+    fs::write(&input, format!("{first}{}\n7\n{}", rest.0, rest.1)).unwrap();
+    let out = dir.join("minimize-day18-min.txt");
+    let _ = fs::remove_file(&out);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["minimize", "day18"])
+        .arg(&input)
+        .args(["--predicate", "panics:expected comma", "--out"])
+        .arg(&out)
+        .output()
+        .expect("running aoc minimize");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(out).unwrap(), "7\n");
+}
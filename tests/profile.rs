@@ -0,0 +1,36 @@
+//! `aoc profile` times every part at every size and fits a growth exponent to them.
+
+use std::{fs, path::PathBuf, process::Command};
+
+#[test]
+fn profile_table_and_plot() {
+    let plot = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("profile-day20.svg");
+    let _ = fs::remove_file(&plot);
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["profile", "day20", "--sizes", "10,20,40", "--repeat", "1"])
+        .arg("--plot")
+        .arg(&plot)
+        .output()
+        .expect("running aoc profile");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows[1], ["size", "part", "1", "part", "2"], "{stdout}");
+    for (row, size) in rows[2..5].iter().zip(["10", "20", "40"]) {
+        assert_eq!(row.len(), 3, "{stdout}");
+        assert_eq!(row[0], size, "{stdout}");
+    }
+    assert_eq!(rows[5][0], "exponent", "{stdout}");
+    assert!(
+        rows[5][1..].iter().all(|e| e.parse::<f64>().is_ok()),
+        "{stdout}"
+    );
+    assert!(fs::read_to_string(&plot).unwrap().contains("<polyline"));
+}
@@ -0,0 +1,60 @@
+//! An input's metadata supplies its params and expected answers, and the command line overrides
+//! them.
+
+use common::{manifest_path, run_on};
+use std::process::{Command, Output};
+
+mod common;
+
+fn example(exe: &str, day: &str, args: &[&str]) -> Output {
+    let input = manifest_path(&format!("inputs/{day}/example.txt"));
+    run_on(Command::new(exe).args(args), &input)
+}
+
+fn day15(args: &[&str]) -> Output {
+    example(env!("CARGO_BIN_EXE_day15"), "day15", args)
+}
+
+#[test]
+fn changed_params_drop_the_files_answers() {
+    let output = day15(&["--set", "row=11"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(!stderr.contains("matches expected"), "{stderr}");
+
+    // setting a param to what the file already says changes nothing
+    let output = day15(&["--set", "row=10"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("part 1 answer matches"), "{stderr}");
+}
+
+#[test]
+fn expect_still_checks_changed_params() {
+    let output = day15(&["--set", "row=11", "--expect", "part1=28"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("part 1 answer matches"), "{stderr}");
+
+    let output = day15(&["--set", "row=11", "--expect", "part1=26"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("part 1 answer 28 doesn't match expected 26 (from --expect)"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn impossible_params_are_errors() {
+    for (param, error) in [
+        ("needed_space=1", "more than the needed_space of 1"),
+        ("total_space=1", "more than the total_space of 1"),
+    ] {
+        let output = example(env!("CARGO_BIN_EXE_day7"), "day7", &["--set", param]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(!stderr.contains("panicked"), "{stderr}");
+        assert!(stderr.contains(error), "{stderr}");
+    }
+}
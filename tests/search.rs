@@ -0,0 +1,69 @@
+//! `--time-budget` cuts a search short and says so; `--search-stats` reports how it went.
+
+use common::{manifest_path, run_on};
+use std::process::{Command, Output};
+
+mod common;
+
+fn day(exe: &str, input: &str, args: &[&str]) -> Output {
+    run_on(Command::new(exe).args(args), &manifest_path(input))
+}
+
+#[test]
+fn stats_without_budget() {
+    let output = day(
+        env!("CARGO_BIN_EXE_day24"),
+        "inputs/day24/example.txt",
+        &["--search-stats"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("best 54 (done)"), "{stderr}");
+    assert!(!stderr.contains("not proven optimal"), "{stderr}");
+}
+
+#[test]
+fn out_of_budget_is_unproven() {
+    let output = day(
+        env!("CARGO_BIN_EXE_day19"),
+        "inputs/day19/example.txt",
+        &["--time-budget", "0"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("part 1 answer"), "{stderr}");
+    assert!(stderr.contains("NOT proven optimal"), "{stderr}");
+}
+
+#[test]
+fn out_of_budget_without_answer() {
+    let output = day(
+        env!("CARGO_BIN_EXE_day24"),
+        "inputs/day24/input.txt",
+        &["--time-budget", "0"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("no path found within --time-budget"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn bad_budget() {
+    for budget in ["-1", "soon"] {
+        let output = day(
+            env!("CARGO_BIN_EXE_day24"),
+            "inputs/day24/example.txt",
+            &["--time-budget", budget],
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(!stderr.contains("panicked"), "{stderr}");
+        assert!(
+            stderr.contains(&format!("bad value \"{budget}\" for --time-budget")),
+            "{stderr}"
+        );
+    }
+}
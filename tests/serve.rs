@@ -0,0 +1,180 @@
+//! `aoc serve`, exercised over loopback.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+};
+
+/// A running server, killed when dropped.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(args: &[&str]) -> Server {
+        let cache_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("aoc-serve-cache");
+        let mut child = Command::new(env!("CARGO_BIN_EXE_aoc"))
+            .args(["serve", "--port", "0"])
+            .args(args)
+            .env("AOC_CACHE_DIR", cache_dir)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("starting aoc serve");
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("listening on http://")
+            .unwrap_or_else(|| panic!("unexpected greeting {line:?}"))
+            .to_owned();
+        Server { child, addr }
+    }
+
+    /// Returns the status code and body.
+    fn request(&self, method: &str, target: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "{method} {target} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\n\r\n{body}",
+            self.addr,
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, body.to_owned())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn input(day: &str, name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs")
+        .join(day)
+        .join(format!("{name}.txt"));
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn answers_both_parts() {
+    let server = Server::start(&["--no-cache"]);
+    let (status, body) = server.request("POST", "/2022/day/1", &input("day1", "example"));
+    assert_eq!(status, 200, "{body}");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["answers"]["1"], "24000");
+    assert_eq!(json["answers"]["2"], "45000");
+    assert!(json["elapsed_ms"].as_f64().unwrap() > 0.0);
+
+    // one run per part for a day that picks its part from a flag
+    let (status, body) = server.request("POST", "/2022/day/5", &input("day5", "example"));
+    assert_eq!(status, 200, "{body}");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["answers"]["1"], "CMZ");
+    assert_eq!(json["answers"]["2"], "MCD");
+}
+
+#[test]
+fn query_sets_params() {
+    let server = Server::start(&["--no-cache"]);
+    let (status, body) = server.request(
+        "POST",
+        "/2022/day/15?row=10&search_max=20",
+        &input("day15", "example"),
+    );
+    assert_eq!(status, 200, "{body}");
+    assert!(body.contains(r#""1":"26""#), "{body}");
+    assert!(body.contains(r#""2":"56000011""#), "{body}");
+}
+
+#[test]
+fn errors() {
+    let server = Server::start(&["--no-cache"]);
+    assert_eq!(server.request("GET", "/2022/day/1", "").0, 405);
+    assert_eq!(server.request("POST", "/2022/day/26", "").0, 404);
+    assert_eq!(server.request("POST", "/elsewhere", "").0, 404);
+    let (status, body) = server.request("POST", "/2022/day/13", "[1,2]\n");
+    assert_eq!(status, 422, "{body}");
+    assert!(body.contains("day13 failed"), "{body}");
+    // day16 on the real input takes far longer than this
+    let (status, body) =
+        server.request("POST", "/2022/day/16?timeout=0.2", &input("day16", "input"));
+    assert_eq!(status, 504, "{body}");
+    for timeout in ["inf", "1e300", "-1", "0", "soon"] {
+        let (status, body) = server.request(
+            "POST",
+            &format!("/2022/day/1?timeout={timeout}"),
+            &input("day1", "example"),
+        );
+        assert_eq!(status, 400, "{timeout}: {body}");
+        assert!(body.contains("bad timeout"), "{body}");
+    }
+}
+
+#[test]
+fn only_the_days_params() {
+    let server = Server::start(&["--no-cache"]);
+    let (status, body) = server.request(
+        "POST",
+        "/2022/day/2?rules=/etc/passwd",
+        &input("day2", "example"),
+    );
+    assert_eq!(status, 400, "{body}");
+    assert!(
+        body.contains(r#"day2 doesn't take a param \"rules\""#),
+        "{body}"
+    );
+}
+
+#[test]
+fn slow_clients_wait_their_turn() {
+    let server = Server::start(&[
+        "--no-cache",
+        "--read-timeout",
+        "0.5",
+        "--max-connections",
+        "1",
+    ]);
+    // holds the only slot while it says nothing
+    let mut idle = TcpStream::connect(&server.addr).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let started = std::time::Instant::now();
+    let (status, body) = server.request("POST", "/2022/day/1", &input("day1", "example"));
+    assert_eq!(status, 200, "{body}");
+    assert!(
+        started.elapsed().as_secs_f64() > 0.3,
+        "didn't wait for the idle connection"
+    );
+
+    let mut response = String::new();
+    idle.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 408 "), "{response}");
+}
+
+#[test]
+fn bad_limits() {
+    for opt in ["--time-limit", "--read-timeout"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+            .args(["serve", "--port", "0", opt, "-1"])
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains(&format!("bad value -1 for {opt}")),
+            "{stderr}"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! The runner steps, observes and stops a simulation as asked.
+
+use aoc2022::simulation::{Runner, Simulation};
+use std::ops::ControlFlow;
+
+/// Counts up to a limit, reporting every multiple of three.
+struct Counter {
+    n: u64,
+    limit: u64,
+}
+
+impl Simulation for Counter {
+    type Event = u64;
+
+    fn step(&mut self) -> Option<u64> {
+        self.n += 1;
+        self.n.is_multiple_of(3).then_some(self.n)
+    }
+
+    fn finished(&self) -> bool {
+        self.n >= self.limit
+    }
+}
+
+#[test]
+fn runs_to_the_end() {
+    let mut counter = Counter { n: 0, limit: 10 };
+    let mut events = vec![];
+    let mut sampled = vec![];
+    let mut at_four = None;
+    let steps = Runner::new()
+        .on_event(|_, _, &n| {
+            events.push(n);
+            Ok(())
+        })
+        .every(5, |counter: &Counter, _| {
+            sampled.push(counter.n);
+            Ok(())
+        })
+        .at(4, |counter| {
+            at_four = Some(counter.n);
+            Ok(())
+        })
+        .run(&mut counter)
+        .unwrap();
+    assert_eq!(steps, 10);
+    assert_eq!(events, [3, 6, 9]);
+    assert_eq!(sampled, [5, 10]);
+    assert_eq!(at_four, Some(4));
+}
+
+#[test]
+fn stops_early() {
+    let mut counter = Counter { n: 0, limit: 100 };
+    let mut runner = Runner::new();
+    assert_eq!(runner.run_n(&mut counter, 7).unwrap(), 7);
+    assert_eq!(
+        runner.run_until(&mut counter, |c, _| c.n == 12).unwrap(),
+        12
+    );
+    let mut runner = runner.observe(|_, _, event| {
+        Ok(match event {
+            Some(_) => ControlFlow::Break(()),
+            None => ControlFlow::Continue(()),
+        })
+    });
+    assert_eq!(runner.run(&mut counter).unwrap(), 15);
+    assert_eq!(counter.n, 15);
+}
@@ -0,0 +1,148 @@
+//! Snapshot tests for the days whose output is a picture.
+//!
+//! Each case runs a day binary on an example input with `AOC_RENDER_DIR` pointed at a scratch
+//! directory, then compares every picture it drew against `tests/snapshots/<case>/<name>.txt`.
+//! Run `./accept-snapshots` (or set `UPDATE_SNAPSHOTS=1`) to accept new renderings.
+
+use similar::TextDiff;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+struct Case {
+    name: &'static str,
+    day: &'static str,
+    exe: &'static str,
+    input: &'static str,
+    args: &'static [&'static str],
+    env: &'static [(&'static str, &'static str)],
+}
+
+fn pictures_in(dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn check_snapshots(case: Case) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let input = manifest_dir
+        .join("inputs")
+        .join(case.day)
+        .join(format!("{}.txt", case.input));
+    let render_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("snapshots")
+        .join(case.name);
+    let _ = fs::remove_dir_all(&render_dir);
+
+    let output = Command::new(case.exe)
+        .args(case.args)
+        .envs(case.env.iter().copied())
+        .env("AOC_RENDER_DIR", &render_dir)
+        .stdin(fs::File::open(&input).expect("open input"))
+        .stderr(Stdio::inherit())
+        .output()
+        .expect("run day binary");
+    assert!(output.status.success(), "{} failed on {:?}", case.name, input);
+
+    let snapshot_dir = manifest_dir.join("tests/snapshots").join(case.name);
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let rendered = pictures_in(&render_dir);
+    let stored = pictures_in(&snapshot_dir);
+    let mut failures = vec![];
+    for name in rendered.union(&stored) {
+        let actual = fs::read_to_string(render_dir.join(name)).ok();
+        let expected = fs::read_to_string(snapshot_dir.join(name)).ok();
+        if actual == expected {
+            continue;
+        }
+        if update {
+            match &actual {
+                Some(actual) => {
+                    fs::create_dir_all(&snapshot_dir).unwrap();
+                    fs::write(snapshot_dir.join(name), actual).unwrap();
+                }
+                None => fs::remove_file(snapshot_dir.join(name)).unwrap(),
+            }
+            continue;
+        }
+        let diff = TextDiff::from_lines(
+            expected.as_deref().unwrap_or(""),
+            actual.as_deref().unwrap_or(""),
+        )
+        .unified_diff()
+        .header(
+            &format!("stored {}/{}", case.name, name),
+            &format!("rendered {}/{}", case.name, name),
+        )
+        .to_string();
+        let what = match (&expected, &actual) {
+            (None, _) => "new picture",
+            (_, None) => "picture no longer drawn",
+            _ => "picture changed",
+        };
+        failures.push(format!("{what}: {}/{name}\n{diff}", case.name));
+    }
+    assert!(
+        failures.is_empty(),
+        "{}\nrun ./accept-snapshots to accept these renderings",
+        failures.join("\n")
+    );
+}
+
+macro_rules! snapshot {
+    ($name:ident, $day:literal, $input:literal $(, args = $args:expr)? $(, env = $env:expr)?) => {
+        #[test]
+        fn $name() {
+            check_snapshots(Case {
+                name: stringify!($name),
+                day: $day,
+                exe: env!(concat!("CARGO_BIN_EXE_", $day)),
+                input: $input,
+                args: &[$($args)?],
+                env: &[$($env)?],
+            });
+        }
+    };
+}
+
+snapshot!(day5_example, "day5", "example");
+snapshot!(day5_example_part2, "day5", "example", args = "--part2");
+snapshot!(day10_example, "day10", "example");
+snapshot!(day14_example, "day14", "example");
+snapshot!(day14_example_part2, "day14", "example", env = ("PART2", "1"));
+snapshot!(day17_example, "day17", "example");
+snapshot!(day23_example, "day23", "example");
+snapshot!(day13_example_explain, "day13", "example", args = "--explain");
+snapshot!(
+    day22_example_explain,
+    "day22",
+    "example",
+    args = "--explain",
+    env = ("AOC_INPUT", "inputs/day22/example.txt")
+);
+
+#[test]
+fn day5_stacks_stay_on_stderr() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new(env!("CARGO_BIN_EXE_day5"))
+        .env_remove("AOC_RENDER_DIR")
+        .stdin(fs::File::open(manifest_dir.join("inputs/day5/example.txt")).unwrap())
+        .output()
+        .expect("run day5");
+    assert!(output.status.success());
+    let stacks =
+        fs::read_to_string(manifest_dir.join("tests/snapshots/day5_example/stacks.txt")).unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Final message: CMZ\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&stacks));
+}